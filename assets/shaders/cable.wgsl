@@ -4,6 +4,14 @@
 var<uniform> dimensions: vec2<f32>;
 @group(2) @binding(1)
 var<uniform> exceeded_length: u32;
+@group(2) @binding(2)
+var<uniform> highlighted: u32;
+@group(2) @binding(3)
+var<uniform> color: vec4<f32>;
+@group(2) @binding(4)
+var<uniform> thickness: f32;
+@group(2) @binding(5)
+var<uniform> overlay_color: vec4<f32>;
 
 @fragment
 fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
@@ -17,14 +25,23 @@ fn fragment(in: VertexOutput) -> @location(0) vec4<f32> {
         1.0
     );
 
-    let thickness = 0.8 / dimensions.y;
+    let line_thickness = thickness / dimensions.y;
     let distance_from_line = abs(uv.y - bent_y);
 
-    if (distance_from_line < thickness) {
+    if (distance_from_line < line_thickness) {
         if (exceeded_length == 1u) {
             return vec4<f32>(1.0, 0.0, 0.0, 1.0); // no cable line
         }
-        return vec4<f32>(0.008, 0.016, 0.063, 1.0); // cable line
+        if (overlay_color.a > 0.0) {
+            return overlay_color; // power overlay
+        }
+        if (highlighted == 2u) {
+            return vec4<f32>(0.95, 0.75, 0.2, 1.0); // selected
+        }
+        if (highlighted == 1u) {
+            return vec4<f32>(0.3, 0.35, 0.5, 1.0); // hovered
+        }
+        return color; // cable line
     }
     return vec4<f32>(0.0, 0.0, 0.0, 0.0);
 }
\ No newline at end of file
@@ -8,6 +8,7 @@ struct PostProcessSettings {
     screen_width: f32,
     screen_height: f32,
     camera_scale: f32,
+    overlay_dim: f32,
 }
 @group(0) @binding(2) var<uniform> settings: PostProcessSettings;
 
@@ -27,5 +28,13 @@ fn fragment(in: FullscreenVertexOutput) -> @location(0) vec4<f32> {
     );
 
     // Sample the texture at the scaled UV coordinates
-    return textureSample(screen_texture, texture_sampler, scaled_uv);
+    let color = textureSample(screen_texture, texture_sampler, scaled_uv);
+
+    // Overlays dim the world, but strongly saturated colors
+    // (the overlay itself) are kept as they are
+    let luma = dot(color.rgb, vec3<f32>(0.299, 0.587, 0.114));
+    let saturation = max(color.r, max(color.g, color.b)) - min(color.r, min(color.g, color.b));
+    let keep = smoothstep(0.35, 0.6, saturation);
+    let dimmed = mix(vec3<f32>(luma * 0.3), color.rgb, keep);
+    return vec4<f32>(mix(color.rgb, dimmed, settings.overlay_dim), color.a);
 }
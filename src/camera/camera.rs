@@ -25,11 +25,17 @@ use super::post_processing::{PostProcessPlugin, PostProcessSettings};
 pub const HIGH_RES_LAYERS: RenderLayers = RenderLayers::layer(0);
 /// Render layers for UI rendering.
 pub const UI_LAYERS: RenderLayers = RenderLayers::layer(1);
+/// Render layers for the planet preview on the world setup screen.
+pub const PREVIEW_LAYERS: RenderLayers = RenderLayers::layer(2);
 const CAMERA_DAMPING: f32 = 1.0; // 1 = no damping 2 = pretty smooth, less than 1 = do not
 const CAMERA_ELEVATION: f32 = 50.0;
+/// How far below / above the player the camera can be panned
+const CAMERA_MIN_ELEVATION: f32 = -5.0;
+const CAMERA_MAX_ELEVATION: f32 = 120.0;
 
 #[derive(Resource)]
 pub struct CameraSettings {
+    /// Elevation above the player (follows the players altitude)
     pub elevation: f32,
     pub is_panning: bool,
     pub total_delta: Vec2,
@@ -181,6 +187,7 @@ impl CameraPlugin {
     ) {
         let pan_speed = 1.0;
         let mut pan_delta = Vec2::ZERO;
+        let player_altitude = player_q.get_single().map_or(0.0, |player| player.altitude);
         
         for event in mouse_motion.read() {
             pan_delta += event.delta;
@@ -205,7 +212,10 @@ impl CameraPlugin {
                     let (surface_pos, surface_angle) = planet.radians_to_radii(pos_angle, 0.0);
                     let surface_radius = surface_pos.length();
                     let current_elevation = pos.length() - surface_radius;
-                    let clamped_elevation = current_elevation.clamp(-5.0, 120.0);
+                    let clamped_elevation = current_elevation.clamp(
+                        player_altitude + CAMERA_MIN_ELEVATION,
+                        player_altitude + CAMERA_MAX_ELEVATION,
+                    );
 
                     if clamped_elevation != current_elevation {
                         let direction = pos.normalize();
@@ -228,7 +238,8 @@ impl CameraPlugin {
                     let pos_angle = pos.y.atan2(pos.x);
                     let (translation, _) = planet.radians_to_radii(pos_angle, 0.0);
 
-                    camera_settings.elevation = (pos.length() - translation.length()).clamp(-5.0, 120.0);
+                    camera_settings.elevation = (pos.length() - translation.length() - player_altitude)
+                        .clamp(CAMERA_MIN_ELEVATION, CAMERA_MAX_ELEVATION);
                     camera_rotation.radians = pos_angle;
                 }
             }
@@ -247,7 +258,7 @@ impl CameraPlugin {
                             planet,
                             camera_rotation.radians,
                             &mut transform,
-                            camera_settings.elevation,
+                            camera_settings.elevation + player.altitude,
                         );
                     }
                 }
@@ -289,6 +289,10 @@ pub struct PostProcessSettings {
     pub screen_height: f32,
     pub camera_scale: f32,
 
+    /// How much the world is dimmed (0.0 - 1.0), used
+    /// by overlays like the power grid view
+    pub overlay_dim: f32,
+
     // WebGL2 structs must be 16 byte aligned.
     // #[allow(unexpected_cfgs)]
     // #[cfg(feature = "webgl2")]
@@ -2,15 +2,14 @@ use std::f32::consts::PI;
 
 /* Imports */
 use bevy::{prelude::*, render::render_resource::{AsBindGroup, ShaderRef}, sprite::{AlphaMode2d, Material2d, Material2dPlugin}};
-use crate::{camera::OuterCamera, ui::info_text::SpawnInfoText, utils::color::hex};
-use super::slot::CableSlot;
+use crate::{camera::OuterCamera, systems::game::PlanetResource, ui::info_text::SpawnInfoText, utils::color::hex};
+use super::{edit::CableEditPlugin, slot::CableSlot, tier::CableTier};
 
 /* Constants */
 const CABLE_Z_INDEX: f32 = 3.0;
 const CABLE_THICKNESS: f32 = 12.5;
 const MAX_HEIGHT_CABLE: f32 = 18.0;
 const CABLE_COLOR: &str = "#020410";
-pub const MAX_CABLE_LENGTH: f32 = 200.0;
 
 /// Plugin to add cable rendering functionality
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
@@ -20,6 +19,31 @@ pub struct CableMaterial {
     /// 0 if not exceeded, 1 if exceeded, bools are not implemented in ShaderType
     #[uniform(1)]
     pub exceeded_length: u32,
+    /// 0 if not highlighted, 1 if hovered, 2 if selected
+    #[uniform(2)]
+    pub highlighted: u32,
+    /// Depends on the cable tier
+    #[uniform(3)]
+    pub color: LinearRgba,
+    #[uniform(4)]
+    pub thickness: f32,
+    /// Drawn instead of the normal color if the alpha
+    /// isn't 0, used by the power overlay
+    #[uniform(5)]
+    pub overlay_color: LinearRgba,
+}
+
+impl CableMaterial {
+    pub fn new(tier: CableTier) -> Self {
+        Self {
+            dimensions: Vec2::new(1.0, 1.0),
+            exceeded_length: 0,
+            highlighted: 0,
+            color: tier.color(),
+            thickness: tier.thickness(),
+            overlay_color: LinearRgba::NONE,
+        }
+    }
 }
 
 impl Material2d for CableMaterial {
@@ -36,7 +60,7 @@ pub struct CablePlugin;
 impl Plugin for CablePlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_plugins(Material2dPlugin::<CableMaterial>::default())
+            .add_plugins((Material2dPlugin::<CableMaterial>::default(), CableEditPlugin))
             .add_systems(Update, (Cable::update_cables, Cable::update_previews));
     }
 }
@@ -51,13 +75,19 @@ pub struct Cable {
 
     pub start_tile_id: usize,
     pub end_tile_id: usize,
+
+    pub tier: CableTier,
+
+    /// What was spent on this cable, refunded when it's removed
+    pub cost: Vec<(PlanetResource, usize)>,
 }
 
 /// Used to highlight the cable between two entities
 /// before the cable is actually spawned
 #[derive(Component)]
 pub struct CablePreview {
-    start_entity: Entity
+    start_entity: Entity,
+    pub tier: CableTier,
 }
 
 impl Cable {
@@ -69,6 +99,8 @@ impl Cable {
 
         start_tile_id: usize,
         end_tile_id: usize,
+        tier: CableTier,
+        cost: Vec<(PlanetResource, usize)>,
         mut cable_materials: ResMut<Assets<CableMaterial>>,
         mut meshes: ResMut<Assets<Mesh>>,
     ) {
@@ -80,7 +112,7 @@ impl Cable {
             //     ..default()
             // },
             Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
-            MeshMaterial2d(cable_materials.add(CableMaterial {dimensions: Vec2::new(1.0, 1.0), exceeded_length: 0})),
+            MeshMaterial2d(cable_materials.add(CableMaterial::new(tier))),
             Transform::from_xyz(0.0, 0.0, CABLE_Z_INDEX),
             Cable {
                 start_entity,
@@ -88,6 +120,9 @@ impl Cable {
 
                 start_tile_id,
                 end_tile_id,
+
+                tier,
+                cost,
             },
         ));
     }
@@ -144,10 +179,11 @@ impl Cable {
     pub fn spawn_preview(
         commands: &mut Commands,
         start_entity: Entity,
+        tier: CableTier,
         mut cable_materials: ResMut<Assets<CableMaterial>>,
         mut meshes: ResMut<Assets<Mesh>>,
     ) {
-        commands.queue(SpawnInfoText("Press ESC to cancel".to_string()));
+        commands.queue(SpawnInfoText(format!("{} - TAB to change, ESC to cancel", tier.display_name())));
         commands.spawn((
             PickingBehavior::IGNORE,
             // Sprite {
@@ -156,10 +192,10 @@ impl Cable {
             //     ..default()
             // },
             Mesh2d(meshes.add(Rectangle::new(1.0, 1.0))),
-            MeshMaterial2d(cable_materials.add(CableMaterial {dimensions: Vec2::new(1.0, 1.0), exceeded_length: 0})),
+            MeshMaterial2d(cable_materials.add(CableMaterial::new(tier))),
             Transform::from_xyz(0.0, 0.0, CABLE_Z_INDEX),
 
-            CablePreview { start_entity },
+            CablePreview { start_entity, tier },
         ));
     }
 
@@ -210,8 +246,10 @@ impl Cable {
 
                 if let Some(material) = cable_material.get_mut(&mesh_material.0) {
                     material.dimensions = Vec2::new(length, height);
+                    material.color = cable.tier.color();
+                    material.thickness = cable.tier.thickness();
 
-                    if length > MAX_CABLE_LENGTH {
+                    if length > cable.tier.max_length() {
                         material.exceeded_length = 1;
                     } else {
                         material.exceeded_length = 0;
@@ -0,0 +1,319 @@
+/* Imports */
+use bevy::prelude::*;
+use crate::{
+    camera::OuterCamera,
+    components::{planet::{Planet, PlayerPlanet}, tile::TILE_SIZE},
+    systems::game::AppState,
+    ui::info_text::SpawnInfoText,
+    utils::logger,
+};
+use super::{cable::{Cable, CableMaterial, CablePreview}, slot::CableSlot, slot_state::SlotCablePlacementResource};
+
+/* Constants */
+/// How close (in world units) the cursor needs to be to a cable to hover it
+const CABLE_HOVER_DISTANCE: f32 = 8.0;
+/// How close the cursor needs to be to the end of the selected cable to grab it
+const CABLE_GRAB_DISTANCE: f32 = TILE_SIZE / 2.0;
+
+/// Keeps track of which cable is hovered, selected
+/// or being rerouted
+#[derive(Resource, Default, Debug)]
+pub struct CableSelection {
+    pub hovered: Option<Entity>,
+    pub selected: Option<Entity>,
+
+    /// The cable being rerouted and which one of its
+    /// ends follows the cursor
+    pub dragging: Option<(Entity, CableEnd)>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CableEnd {
+    Start,
+    End,
+}
+
+impl CableSelection {
+    /// Deselects everything
+    fn reset(&mut self) {
+        self.hovered = None;
+        self.selected = None;
+        self.dragging = None;
+    }
+}
+
+pub struct CableEditPlugin;
+impl Plugin for CableEditPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<CableSelection>()
+            .add_systems(Update, (
+                Self::hover,
+                Self::on_mouse,
+                Self::on_keyboard,
+                Self::update_highlights,
+            ).chain().run_if(in_state(AppState::InGame)));
+    }
+}
+
+impl CableEditPlugin {
+    /// Finds the cable closest to the cursor
+    fn hover(
+        mut selection: ResMut<CableSelection>,
+        slot_res: Res<SlotCablePlacementResource>,
+        cables_q: Query<(Entity, &Cable)>,
+        slots_q: Query<&GlobalTransform, With<CableSlot>>,
+        windows_q: Query<&Window>,
+        camera_q: Query<(&Camera, &GlobalTransform), With<OuterCamera>>,
+    ) -> () {
+        selection.hovered = None;
+
+        // Cables can't be selected while placing or rerouting one
+        if slot_res.active().is_some() || selection.dragging.is_some() { return }
+        let Some(cursor) = cursor_world_position(&windows_q, &camera_q) else { return };
+
+        let mut closest: Option<(Entity, f32)> = None;
+        for (entity, cable) in cables_q.iter() {
+            let Some((start, end)) = cable_ends(cable, &slots_q) else { continue };
+
+            // The ends are covered by the cable slots, which
+            // should still be clickable
+            let Some(distance) = distance_to_cable(cursor, start, end, CABLE_GRAB_DISTANCE) else { continue };
+            if distance < CABLE_HOVER_DISTANCE && closest.map_or(true, |(_, d)| distance < d) {
+                closest = Some((entity, distance));
+            }
+        }
+
+        selection.hovered = closest.map(|(entity, _)| entity);
+    }
+
+    /// Selects cables, and starts / finishes rerouting
+    fn on_mouse(
+        mut commands: Commands,
+        mut selection: ResMut<CableSelection>,
+        mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+        mb: Res<ButtonInput<MouseButton>>,
+        slot_res: Res<SlotCablePlacementResource>,
+        mut cables_q: Query<(&mut Cable, &mut Visibility)>,
+        slots_q: Query<(Entity, &CableSlot, &GlobalTransform)>,
+        slot_transforms_q: Query<&GlobalTransform, With<CableSlot>>,
+        cable_preview_q: Query<Entity, With<CablePreview>>,
+        windows_q: Query<&Window>,
+        camera_q: Query<(&Camera, &GlobalTransform), With<OuterCamera>>,
+        cable_materials: ResMut<Assets<CableMaterial>>,
+        meshes: ResMut<Assets<Mesh>>,
+    ) -> () {
+        if slot_res.active().is_some() {
+            selection.selected = None;
+            return
+        }
+        let cursor = cursor_world_position(&windows_q, &camera_q);
+
+        /* Finish rerouting */
+        if let Some((cable_entity, moving_end)) = selection.dragging {
+            if !mb.just_released(MouseButton::Left) { return }
+            Cable::remove_previews(&mut commands, cable_preview_q);
+            selection.dragging = None;
+
+            let Ok((mut cable, mut visibility)) = cables_q.get_mut(cable_entity) else { return };
+            *visibility = Visibility::Inherited;
+            let Ok(mut planet) = planet_q.get_single_mut() else { return };
+            let Some(cursor) = cursor else { return };
+
+            // The slot closest to the cursor is the new end
+            let Some((new_entity, new_slot, new_transform)) = slots_q.iter()
+                .filter(|(_, _, transform)| transform.translation().truncate().distance(cursor) < TILE_SIZE)
+                .min_by(|(_, _, a), (_, _, b)| {
+                    a.translation().truncate().distance(cursor)
+                        .total_cmp(&b.translation().truncate().distance(cursor))
+                })
+                else { return };
+
+            let (fixed_entity, fixed_tile_id, old_tile_id) = match moving_end {
+                CableEnd::Start => (cable.end_entity, cable.end_tile_id, cable.start_tile_id),
+                CableEnd::End => (cable.start_entity, cable.start_tile_id, cable.end_tile_id),
+            };
+            let Ok(fixed_transform) = slot_transforms_q.get(fixed_entity) else { return };
+
+            // Dropped where it was picked up
+            if new_slot.tile_id == old_tile_id { return }
+            if new_slot.tile_id == fixed_tile_id
+                || planet.powergrid_tiles_are_connected(fixed_tile_id, new_slot.tile_id) {
+                commands.queue(SpawnInfoText("Already connected".to_string()));
+                return
+            }
+            let length = fixed_transform.translation().truncate()
+                .distance(new_transform.translation().truncate());
+            if length > cable.tier.max_length() {
+                commands.queue(SpawnInfoText("Cable is too long".to_string()));
+                return
+            }
+
+            // Pay for the new length, the old cable is refunded
+            let cost = cable.tier.cost(length);
+            if let Err(e) = planet.resources.try_replace(&cable.cost, &cost) {
+                commands.queue(SpawnInfoText(e));
+                return
+            }
+            cable.cost = cost;
+
+            logger::log::blue("cable", format!("Rerouting cable from {} to {}", old_tile_id, new_slot.tile_id));
+            planet.powergrid_unregister_connection(fixed_tile_id, old_tile_id);
+            planet.powergrid_register_cable(fixed_tile_id, new_slot.tile_id, cable.tier);
+            match moving_end {
+                CableEnd::Start => {
+                    cable.start_entity = new_entity;
+                    cable.start_tile_id = new_slot.tile_id;
+                },
+                CableEnd::End => {
+                    cable.end_entity = new_entity;
+                    cable.end_tile_id = new_slot.tile_id;
+                },
+            }
+
+            return
+        }
+
+        if !mb.just_pressed(MouseButton::Left) { return }
+        let Some(cursor) = cursor else { return };
+
+        /* Start rerouting if one of the ends of the selected cable was grabbed */
+        if let Some(cable_entity) = selection.selected {
+            if let Ok((cable, mut visibility)) = cables_q.get_mut(cable_entity) {
+                if let Some((start, end)) = cable_ends(&cable, &slot_transforms_q) {
+                    let tier = cable.tier;
+                    let grabbed = if cursor.distance(start) < CABLE_GRAB_DISTANCE {
+                        Some((CableEnd::Start, cable.end_entity))
+                    } else if cursor.distance(end) < CABLE_GRAB_DISTANCE {
+                        Some((CableEnd::End, cable.start_entity))
+                    } else {
+                        None
+                    };
+
+                    if let Some((moving_end, fixed_entity)) = grabbed {
+                        *visibility = Visibility::Hidden;
+                        selection.dragging = Some((cable_entity, moving_end));
+                        Cable::spawn_preview(&mut commands, fixed_entity, tier, cable_materials, meshes);
+                        return
+                    }
+                }
+            }
+        }
+
+        selection.selected = selection.hovered;
+        if selection.selected.is_some() {
+            commands.queue(SpawnInfoText("Drag an end to reroute, DEL to remove".to_string()));
+        }
+    }
+
+    /// Removing the selected cable & cancelling
+    fn on_keyboard(
+        mut commands: Commands,
+        mut selection: ResMut<CableSelection>,
+        mut cables_q: Query<&mut Visibility, With<Cable>>,
+        kb: Res<ButtonInput<KeyCode>>,
+    ) -> () {
+        if kb.just_pressed(KeyCode::Escape) {
+            // The slot plugin removes the cable preview
+            if let Some((cable_entity, _)) = selection.dragging {
+                if let Ok(mut visibility) = cables_q.get_mut(cable_entity) {
+                    *visibility = Visibility::Inherited;
+                }
+            }
+            selection.reset();
+        }
+
+        if selection.dragging.is_some() { return }
+        if kb.just_pressed(KeyCode::Delete) || kb.just_pressed(KeyCode::KeyX) {
+            if let Some(entity) = selection.selected {
+                commands.queue(RemoveCableCommand { entity, refund: true });
+                selection.reset();
+            }
+        }
+    }
+
+    /// Hovered and selected cables are drawn in another color
+    fn update_highlights(
+        mut selection: ResMut<CableSelection>,
+        cables_q: Query<(Entity, &MeshMaterial2d<CableMaterial>), With<Cable>>,
+        mut cable_materials: ResMut<Assets<CableMaterial>>,
+    ) -> () {
+        // Cables might have been removed together with a tile
+        if selection.selected.is_some_and(|entity| !cables_q.contains(entity)) {
+            selection.reset();
+        }
+
+        for (entity, mesh_material) in cables_q.iter() {
+            let highlighted = if selection.selected == Some(entity) { 2 }
+                else if selection.hovered == Some(entity) { 1 }
+                else { 0 };
+
+            // Only touch the material if it changed, it
+            // would otherwise be re-uploaded every frame
+            if cable_materials.get(&mesh_material.0).is_some_and(|m| m.highlighted != highlighted) {
+                if let Some(material) = cable_materials.get_mut(&mesh_material.0) {
+                    material.highlighted = highlighted;
+                }
+            }
+        }
+    }
+}
+
+/// Removes a cable, disconnects the two tiles
+/// and refunds whatever the cable cost if `refund`
+pub struct RemoveCableCommand {
+    pub entity: Entity,
+    pub refund: bool,
+}
+impl Command for RemoveCableCommand {
+    fn apply(self, world: &mut World) {
+        let Some(cable) = world.get::<Cable>(self.entity) else { return };
+        let (a, b, cost) = (cable.start_tile_id, cable.end_tile_id, cable.cost.clone());
+
+        let mut query_state = world.query_filtered::<&mut Planet, With<PlayerPlanet>>();
+        if let Ok(mut planet) = query_state.get_single_mut(world) {
+            planet.powergrid_unregister_connection(a, b);
+            if self.refund {
+                for (resource, amount) in cost {
+                    planet.resources.refund(resource, amount);
+                }
+            }
+        }
+
+        logger::log::blue("cable", format!("Removed cable between {} and {}", a, b));
+        DespawnRecursive { entity: self.entity, warn: true }.apply(world);
+    }
+}
+
+/// World position of the cursor
+fn cursor_world_position(
+    windows_q: &Query<&Window>,
+    camera_q: &Query<(&Camera, &GlobalTransform), With<OuterCamera>>,
+) -> Option<Vec2> {
+    let window = windows_q.get_single().ok()?;
+    let (camera, camera_transform) = camera_q.get_single().ok()?;
+    window
+        .cursor_position()
+        .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
+}
+
+/// World positions of the two slots a cable is connected to
+fn cable_ends(cable: &Cable, slots_q: &Query<&GlobalTransform, With<CableSlot>>) -> Option<(Vec2, Vec2)> {
+    let start = slots_q.get(cable.start_entity).ok()?.translation().truncate();
+    let end = slots_q.get(cable.end_entity).ok()?.translation().truncate();
+    Some((start, end))
+}
+
+/// Distance from `point` to the line between `start` and `end`.
+/// Returns None if the closest point is within `margin` of
+/// either end.
+fn distance_to_cable(point: Vec2, start: Vec2, end: Vec2, margin: f32) -> Option<f32> {
+    let direction = end - start;
+    let length = direction.length();
+    if length <= margin * 2.0 { return None }
+
+    let along = (point - start).dot(direction / length);
+    if along < margin || along > length - margin { return None }
+
+    Some((point - (start + direction / length * along)).length())
+}
@@ -1,3 +1,5 @@
 pub mod cable;
+pub mod edit;
 pub mod slot;
 pub mod slot_state;
+pub mod tier;
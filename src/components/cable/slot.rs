@@ -1,11 +1,11 @@
 /* Imports */
-use super::{cable::CableMaterial, slot_state::SlotCablePlacementResource};
+use super::{cable::CableMaterial, edit::CableSelection, slot_state::SlotCablePlacementResource};
 use crate::{
     camera::HIGH_RES_LAYERS, components::{
-        cable::cable::{Cable, CablePreview, MAX_CABLE_LENGTH},
+        cable::cable::{Cable, CablePreview},
         planet::{Planet, PlayerPlanet},
         tile::TILE_SIZE
-    }, systems::game::GameState, ui::stats::OpenStats, utils::{color::hex, logger}
+    }, systems::game::{AppState, GameState, LifetimeStat, Profile}, ui::{info_text::SpawnInfoText, stats::OpenStats}, utils::{color::hex, logger}
 };
 use bevy::{ecs::{entity, event}, prelude::*};
 
@@ -18,7 +18,7 @@ const SLOT_HIGHLIGHT_COLOR: &'static str = "#00000044";
 pub struct CableSlotPlugin;
 impl Plugin for CableSlotPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(Update, (CableSlot::breathe, CableSlot::on_cancel))
+        app.add_systems(Update, (CableSlot::breathe, (CableSlot::on_cancel, CableSlot::on_change_tier).run_if(in_state(AppState::InGame))))
         .init_resource::<SlotCablePlacementResource>();
     }
 }
@@ -92,9 +92,14 @@ impl CableSlot {
         mut events: EventWriter<OpenStats>,
         cable_materials: ResMut<Assets<CableMaterial>>,
         meshes: ResMut<Assets<Mesh>>,
+        cable_selection: Res<CableSelection>,
+        mut profile: ResMut<Profile>,
     ) {
         click.propagate(false);
 
+        // Releasing a rerouted cable over a slot is handled by the cable editing
+        if cable_selection.dragging.is_some() { return }
+
         let mut planet = planet_q.single_mut();
         let mut highlight_all = false;
         let mut needs_highlight_reset = false;
@@ -105,35 +110,45 @@ impl CableSlot {
                 Cable::remove_previews(&mut commands, cable_preview_q);
                 needs_highlight_reset = true;
                 let occupied = planet.powergrid_tiles_are_connected(id, slot.tile_id);
+                let tier = slot_res.tier;
+                let length = slot_res.start_entity_pos.distance(transform.translation.truncate().xy());
+                let cost = tier.cost(length);
 
                 if occupied
                     || id == slot.tile_id
-                    || slot_res.start_entity_pos.distance(transform.translation.truncate().xy()) > MAX_CABLE_LENGTH {
+                    || length > tier.max_length() {
+                    slot_res.reset();
+                }else if let Err(e) = planet.resources.try_spend(&cost) {
+                    logger::log::red("cable", &e);
+                    commands.queue(SpawnInfoText(e));
                     slot_res.reset();
                 }else {
-                    logger::log::blue("cable", format!("Spawning cable between {} and {}", id, slot.tile_id));
+                    logger::log::blue("cable", format!("Spawning {:?} cable between {} and {}", tier, id, slot.tile_id));
 
                     /* Spawn cable */
                     commands.entity(planet.planet_entity()).with_children(|parent| {
                         Cable::spawn_between_slots(
                             parent,
-                            click.entity(),
                             other_entity,
+                            click.entity(),
                             id,
                             slot.tile_id,
+                            tier,
+                            cost,
                             cable_materials,
                             meshes,
                         );
                     });
 
                     /* Register connection to game state and reset */
-                    planet.powergrid_register_connection(id, slot.tile_id);
+                    planet.powergrid_register_cable(id, slot.tile_id, tier);
+                    profile.add(LifetimeStat::CablesLaid, 1.0);
                     slot_res.reset();
                 }
             } else {
                 slot_res.set_active(slot.tile_id, click.entity(), transform.translation);
                 Cable::spawn_preview(
-                    &mut commands, click.entity(),
+                    &mut commands, click.entity(), slot_res.tier,
                     cable_materials, meshes,
                 );
                 Self::highlight(
@@ -277,6 +292,29 @@ impl CableSlot {
         }
     }
 
+    // Tab
+    fn on_change_tier(
+        kb: Res<ButtonInput<KeyCode>>,
+        mut slot_res: ResMut<SlotCablePlacementResource>,
+        mut cable_preview_q: Query<&mut CablePreview>,
+        mut commands: Commands,
+    ) {
+        if kb.just_pressed(KeyCode::Tab) {
+            slot_res.tier = slot_res.tier.next();
+            let tier = slot_res.tier;
+            if slot_res.active().is_some() {
+                for mut preview in cable_preview_q.iter_mut() { preview.tier = tier; }
+            }
+
+            commands.queue(SpawnInfoText(format!(
+                "{} - {} copper / 100m, max {}m",
+                tier.display_name(),
+                tier.copper_per_100(),
+                tier.max_length() as usize,
+            )));
+        }
+    }
+
     // Idle animation
     fn breathe(time: Res<Time>, mut query: Query<(&mut Transform, &Self)>) {
         for (mut transform, slot) in query.iter_mut() {
@@ -323,9 +361,19 @@ impl Command for RemoveCableSlotCommand {
         if self.remove_visual_cables {
             let mut cable_q = commands.query_filtered::<(Entity, &Cable), With<Cable>>();
             let mut entities_to_despawn = Vec::new();
+            let mut refund = Vec::new();
             for (entity, cable) in cable_q.iter(commands) {
                 if cable.start_tile_id == tile_id || cable.end_tile_id == tile_id {
                     entities_to_despawn.push(entity);
+                    refund.extend(cable.cost.iter().cloned());
+                }
+            }
+
+            /* Refund the cables */
+            let mut planet_q = commands.query_filtered::<&mut Planet, With<PlayerPlanet>>();
+            if let Ok(mut planet) = planet_q.get_single_mut(commands) {
+                for (resource, amount) in refund {
+                    planet.resources.refund(resource, amount);
                 }
             }
 
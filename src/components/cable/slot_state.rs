@@ -1,5 +1,6 @@
 /* Imports */
 use bevy::prelude::*;
+use super::tier::CableTier;
 
 /// Keeps track of slots that are clicked
 #[derive(Resource, Default, Debug)]
@@ -10,8 +11,12 @@ pub struct SlotCablePlacementResource {
     /// The transform of the start entity. (not global)
     /// will be compared to the global transform of the
     /// end entity to determine if the cable is valid
-    /// (no longer than MAX_CABLE_LENGTH)
+    /// (no longer than the max length of the tier)
     pub start_entity_pos: Vec2,
+
+    /// What tier of cable will be placed. Stays the
+    /// same between placements.
+    pub tier: CableTier,
 }
 
 impl SlotCablePlacementResource {
@@ -22,7 +27,9 @@ impl SlotCablePlacementResource {
     pub fn reset(&mut self) { self.active = None; }
     pub fn active(&self) -> Option<(usize, Entity)> { self.active }
 }
-fn order(a: usize, b: usize) -> (usize, usize) {
+/// Two tiles in the same order no matter which one
+/// is the start, used as a key for cables
+pub fn order(a: usize, b: usize) -> (usize, usize) {
     if a > b {
         (b, a)
     }else {
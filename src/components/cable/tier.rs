@@ -0,0 +1,84 @@
+/* Imports */
+use bevy::prelude::*;
+use crate::{systems::game::PlanetResource, utils::color::hex};
+
+/// Cables come in different tiers. Better cables
+/// reach further and carry more energy, but cost
+/// more copper per length.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum CableTier {
+    #[default]
+    Basic,
+    Insulated,
+    HighVoltage,
+}
+
+impl CableTier {
+    /// Used to cycle through the tiers when placing cables
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Basic => Self::Insulated,
+            Self::Insulated => Self::HighVoltage,
+            Self::HighVoltage => Self::Basic,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Basic => "Copper cable",
+            Self::Insulated => "Insulated cable",
+            Self::HighVoltage => "High-voltage cable",
+        }
+    }
+
+    /// How far apart two slots can be
+    pub fn max_length(&self) -> f32 {
+        match self {
+            Self::Basic => 200.0,
+            Self::Insulated => 300.0,
+            Self::HighVoltage => 450.0,
+        }
+    }
+
+    /// How much energy can pass through the cable every tick
+    pub fn max_throughput(&self) -> f32 {
+        match self {
+            Self::Basic => 10.0,
+            Self::Insulated => 25.0,
+            Self::HighVoltage => 80.0,
+        }
+    }
+
+    /// How much copper 100 units of cable costs
+    pub(crate) fn copper_per_100(&self) -> f32 {
+        match self {
+            Self::Basic => 2.0,
+            Self::Insulated => 4.0,
+            Self::HighVoltage => 8.0,
+        }
+    }
+
+    /// What a cable of this tier costs, at least 1 copper
+    pub fn cost(&self, length: f32) -> Vec<(PlanetResource, usize)> {
+        let copper = (length / 100.0 * self.copper_per_100()).ceil().max(1.0) as usize;
+        vec![(PlanetResource::Copper, copper)]
+    }
+
+    /// The color of the cable line (passed to the shader)
+    pub fn color(&self) -> LinearRgba {
+        match self {
+            Self::Basic => hex!("#020410"),
+            Self::Insulated => hex!("#5a1f1f"),
+            Self::HighVoltage => hex!("#b8b8c8"),
+        }.to_linear()
+    }
+
+    /// How thick the cable line is (passed to the shader)
+    pub fn thickness(&self) -> f32 {
+        match self {
+            Self::Basic => 0.8,
+            Self::Insulated => 1.1,
+            Self::HighVoltage => 1.5,
+        }
+    }
+}
@@ -0,0 +1,176 @@
+/* Imports */
+use std::f32::consts::{PI, TAU};
+use bevy::{prelude::*, sprite::Anchor};
+use crate::{
+    components::{
+        planet::{Planet, PlayerPlanet},
+        player::{health::{DamageSource, PlayerDamageEvent, PlayerHealth}, player::Player},
+        tile::{damage::DamageTileEvent, TILE_SIZE},
+    },
+    functional::damageable::{Damageable, DamageType, HitMaterial, LootTable, Resistances},
+    systems::{game::{AppState, GameSpeed, GameTickSet, PlanetResource}, traits::GenericTile},
+    ui::info_text::SpawnInfoText,
+    utils::logger,
+};
+use super::spawner::CreatureSpawnerPlugin;
+
+/* Constants */
+const CREATURE_HEALTH: f32 = 25.0;
+/// Walking speed along the surface (units / s)
+const CREATURE_SPEED: f32 = 14.0;
+const CREATURE_Z: f32 = 9.0;
+/// How many tiles away creatures notice tiles and the player
+const SIGHT_RANGE: usize = 40;
+/// How close (along the surface) a creature needs
+/// to be to attack, measured from the edge of tiles
+const ATTACK_REACH: f32 = 14.0;
+/// Damage every tick to whatever a creature is attacking
+const TILE_DAMAGE: f32 = 4.0;
+const PLAYER_DAMAGE: f32 = 6.0;
+
+/// Something hostile that walks the planet surface
+/// and attacks tiles and the player
+#[derive(Component)]
+pub struct Creature {
+    pub radians: f32,
+
+    /// Which way it walks when there's nothing
+    /// in sight, 1.0 or -1.0
+    wander_direction: f32,
+}
+
+/// What a creature is going for
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CreatureTarget {
+    Tile(usize),
+    Player,
+}
+
+impl Creature {
+    pub fn spawn(
+        commands: &mut Commands,
+        asset_server: &AssetServer,
+        planet: &Planet,
+        radians: f32,
+        wander_direction: f32,
+    ) -> Entity {
+        commands.spawn((
+            Creature { radians, wander_direction },
+            Sprite {
+                image: asset_server.load("creatures/crawler.png"),
+                anchor: Anchor::BottomCenter,
+                ..default()
+            },
+            planet.radians_to_transform(radians, 0.0, CREATURE_Z),
+            Damageable::new(CREATURE_HEALTH, HitMaterial::Flesh, Self::on_killed)
+                .with_resistances(Resistances::none()
+                    .with(DamageType::Blunt, 0.2))
+                .with_loot(LootTable::new(1)
+                    .with(1, PlanetResource::Copper, 1..=2)),
+        ))
+        .observe(Damageable::on_clicked)
+        .id()
+    }
+
+    fn on_killed(world: &mut World, entity: Entity, _position: Vec2) -> () {
+        logger::log::bright_green("creature", format!("Creature {} was killed", entity));
+        world.commands().queue(SpawnInfoText("Creature killed".to_string()));
+    }
+
+    /// The closest tile or player within sight, with the angle
+    /// to it and how close the creature needs to get to attack
+    fn find_target(&self, planet: &Planet, player_radians: Option<f32>) -> Option<(CreatureTarget, f32, f32)> {
+        let angle_to = |radians: f32| (radians - self.radians + PI).rem_euclid(TAU) - PI;
+        let index = planet.radians_to_index(self.radians);
+
+        let tiles = planet.numbers_in_radius(index, SIGHT_RANGE).into_iter()
+            .filter_map(|index| planet.tiles.get(&index))
+            .filter(|tile| !tile.tile_type.indestructible() && !tile.is_broken())
+            .map(|tile| (
+                CreatureTarget::Tile(tile.tile_id),
+                angle_to(tile.tile_id as f32 * planet.angular_step()),
+                ATTACK_REACH + tile.width() as f32 * TILE_SIZE / 2.0,
+            ));
+        let player = player_radians
+            .map(|radians| (CreatureTarget::Player, angle_to(radians), ATTACK_REACH))
+            .filter(|(_, angle, _)| angle.abs() * planet.radius() <= SIGHT_RANGE as f32 * TILE_SIZE);
+
+        // Closest edge, not closest center
+        let distance = |(_, angle, reach): &(CreatureTarget, f32, f32)| angle.abs() * planet.radius() - reach;
+        tiles.chain(player).min_by(|a, b| distance(a).total_cmp(&distance(b)))
+    }
+
+    /// Where the player is, unless it's dead
+    fn player_radians(player_q: &Query<(&Player, &PlayerHealth)>) -> Option<f32> {
+        player_q.iter()
+            .find(|(_, health)| !health.is_dead())
+            .map(|(player, _)| player.radians)
+    }
+
+    /// Walks towards the closest target until it's within reach,
+    /// or wanders around if there is none. Follows the game speed.
+    fn update(
+        time: Res<Time>,
+        speed: Res<GameSpeed>,
+        mut creature_q: Query<(&mut Creature, &mut Transform, &mut Sprite)>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        player_q: Query<(&Player, &PlayerHealth)>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        let player_radians = Self::player_radians(&player_q);
+        let step = CREATURE_SPEED / planet.radius() * time.delta_secs() * speed.time_scale();
+
+        for (mut creature, mut transform, mut sprite) in creature_q.iter_mut() {
+            let direction = match creature.find_target(planet, player_radians) {
+                Some((_, angle, reach)) if angle.abs() * planet.radius() <= reach => 0.0,
+                Some((_, angle, _)) => angle.signum(),
+                None => creature.wander_direction,
+            };
+
+            if direction != 0.0 {
+                creature.radians = Planet::normalize_radians(creature.radians + step * direction);
+                sprite.flip_x = direction > 0.0;
+            }
+
+            let new_transform = planet.radians_to_transform(creature.radians, 0.0, CREATURE_Z);
+            transform.translation = new_transform.translation;
+            transform.rotation = new_transform.rotation;
+        }
+    }
+
+    /// Creatures hurt whatever they reached every tick
+    fn attack(
+        creature_q: Query<&Creature>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        player_q: Query<(&Player, &PlayerHealth)>,
+        mut tile_damage: EventWriter<DamageTileEvent>,
+        mut player_damage: EventWriter<PlayerDamageEvent>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        let player_radians = Self::player_radians(&player_q);
+
+        for creature in creature_q.iter() {
+            let Some((target, angle, reach)) = creature.find_target(planet, player_radians) else { continue };
+            if angle.abs() * planet.radius() > reach { continue }
+
+            match target {
+                CreatureTarget::Tile(tile_id) => {
+                    tile_damage.send(DamageTileEvent { tile_id, damage: TILE_DAMAGE });
+                },
+                CreatureTarget::Player => {
+                    player_damage.send(PlayerDamageEvent { amount: PLAYER_DAMAGE, source: DamageSource::Creature });
+                },
+            }
+        }
+    }
+}
+
+pub struct CreaturePlugin;
+impl Plugin for CreaturePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_plugins(CreatureSpawnerPlugin)
+            .add_systems(Update, Creature::update.run_if(in_state(AppState::InGame)))
+            .add_systems(FixedUpdate, Creature::attack.in_set(GameTickSet));
+    }
+}
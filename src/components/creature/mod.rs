@@ -0,0 +1,4 @@
+pub mod spawner;
+
+mod creature;
+pub use creature::*;
@@ -0,0 +1,88 @@
+/* Imports */
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::{
+    components::{planet::{Planet, PlayerPlanet}, player::player::Player, poi::{tree::Tree, PointOfInterestType}},
+    systems::game::{AppState, GameState, GameTickSet},
+    utils::logger,
+};
+use super::Creature;
+
+/* Constants */
+const MAX_CREATURES: usize = 8;
+/// Chance every tick that a creature crawls out somewhere dark
+const NIGHT_SPAWN_CHANCE: f64 = 0.05;
+/// Chance every tick that a creature comes out of a forest, day or night
+const FOREST_SPAWN_CHANCE: f64 = 0.02;
+/// At least this many trees within `FOREST_RADIUS` tiles make a forest
+const FOREST_TREES: usize = 4;
+const FOREST_RADIUS: usize = 5;
+/// Creatures never spawn closer to the player than this (in tiles)
+const MIN_PLAYER_DISTANCE: usize = 25;
+
+/// Decides when and where creatures show up. Seeded
+/// with the game seed, like the disasters.
+#[derive(Resource)]
+pub struct CreatureSpawner {
+    rng: ChaCha8Rng,
+}
+
+impl CreatureSpawner {
+    pub fn new(seed: u64) -> Self {
+        // Not the same numbers as the disasters
+        Self { rng: ChaCha8Rng::seed_from_u64(seed.wrapping_add(1)) }
+    }
+
+    fn setup(mut commands: Commands, game_state: Res<GameState>) -> () {
+        commands.insert_resource(Self::new(game_state.game_seed));
+    }
+
+    /// Picks a random place every tick, creatures come
+    /// out there if it's dark or if it's in a forest
+    fn tick(
+        mut commands: Commands,
+        mut spawner: ResMut<CreatureSpawner>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        creature_q: Query<(), With<Creature>>,
+        player_q: Query<&Player>,
+        asset_server: Res<AssetServer>,
+    ) -> () {
+        if creature_q.iter().count() >= MAX_CREATURES { return }
+        let Ok(planet) = planet_q.get_single() else { return };
+        let rng = &mut spawner.rng;
+
+        let index = rng.gen_range(0..planet.tile_places());
+        let close_to_player = player_q.iter().any(|player| {
+            planet.index_distance(planet.radians_to_index(player.radians), index) < MIN_PLAYER_DISTANCE
+        });
+        if close_to_player { return }
+
+        let mut chance = 0.0;
+        if !planet.is_daylight(index) { chance += NIGHT_SPAWN_CHANCE; }
+        if Self::is_forest(planet, index) { chance += FOREST_SPAWN_CHANCE; }
+        if !rng.gen_bool(chance) { return }
+
+        let wander_direction = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+        Creature::spawn(&mut commands, &asset_server, planet, index as f32 * planet.angular_step(), wander_direction);
+        logger::log::yellow("creature", format!("Creature spawned at {}", index));
+    }
+
+    fn is_forest(planet: &Planet, index: usize) -> bool {
+        let tree = PointOfInterestType::Tree(Tree::new());
+        planet.numbers_in_radius(index, FOREST_RADIUS).iter()
+            .filter_map(|index| planet.points_of_interest.get(index))
+            .flatten()
+            .filter(|poi| poi.poi_type == tree)
+            .count() >= FOREST_TREES
+    }
+}
+
+pub struct CreatureSpawnerPlugin;
+impl Plugin for CreatureSpawnerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::Loading), CreatureSpawner::setup.after(Planet::setup))
+            .add_systems(FixedUpdate, CreatureSpawner::tick.in_set(GameTickSet));
+    }
+}
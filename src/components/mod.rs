@@ -5,3 +5,4 @@ pub mod tile;
 pub mod debug;
 pub mod poi;
 pub mod player;
+pub mod creature;
@@ -14,6 +14,11 @@ pub struct PlanetConfiguration {
     pub resolution: usize,
     pub amplitude: f32,
     pub frequency: f64,
+
+    /// Multiplies how likely trees, stones and copper
+    /// are to spawn on every tile place
+    #[inspector(min = 0.0)]
+    pub resource_abundance: f32,
 }
 
 impl Default for PlanetConfiguration {
@@ -24,11 +29,26 @@ impl Default for PlanetConfiguration {
             resolution: 500,
             amplitude: 2000.0,
             frequency: 80.0,
+            resource_abundance: 1.0,
         }
     }
 }
 
-/// On update configuration (system)
+impl PlanetConfiguration {
+    /// If `planet` was generated from this configuration
+    pub fn generated(&self, planet: &Planet) -> bool {
+        planet.seed == self.seed
+            && planet.radius == self.radius.max(15.0)
+            && planet.resolution == self.resolution
+            && planet.amplitude == self.amplitude
+            && planet.frequency == self.frequency
+            && planet.resource_abundance == self.resource_abundance
+    }
+}
+
+/// Regenerates the planet when the configuration is edited in
+/// the inspector. Systems see every resource as changed the
+/// first time they run, so we compare with the planet as well.
 pub fn on_update(
     config: ResMut<PlanetConfiguration>,
     mut commands: Commands,
@@ -40,7 +60,8 @@ pub fn on_update(
     asset_server: Res<AssetServer>,
 ) -> () {
     if config.is_changed() {
-        if let Ok((_planet, entity)) = planet_q.get_single() {
+        if let Ok((planet, entity)) = planet_q.get_single() {
+            if config.generated(planet) { return }
             match commands.get_entity(entity) {
                 Some(e) => e.despawn_recursive(),
                 None => ()
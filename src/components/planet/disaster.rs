@@ -0,0 +1,248 @@
+/* Imports */
+use std::{f32::consts::{PI, TAU}, ops::Range};
+use bevy::prelude::*;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use crate::{
+    components::{cable::{cable::Cable, edit::RemoveCableCommand}, tile::{damage::DamageTileEvent, TILE_SIZE}},
+    systems::{game::{AppState, GameState, GameTickSet}, traits::GenericTile},
+    ui::info_text::SpawnInfoText,
+    utils::logger,
+};
+use super::{mesh::generate_planet_mesh, Planet, PlanetAtmosphereMaterial, PlayerPlanet};
+
+/* Constants */
+/// Ticks between two disasters, picked at random
+const DISASTER_INTERVAL: Range<u32> = 300..900;
+/// Chance every tick that a meteor lands during a shower
+const METEOR_CHANCE: f64 = 0.6;
+/// How far (along the surface) a meteor hurts things
+const METEOR_RADIUS: f32 = 30.0;
+const METEOR_DAMAGE: f32 = 35.0;
+/// How wide (along the surface, to each side) and deep a crater is
+const CRATER_WIDTH: f32 = 40.0;
+const CRATER_DEPTH: f32 = 6.0;
+/// Damage every tick during a storm, for a tile this tall.
+/// Taller tiles catch more wind.
+const STORM_DAMAGE: f32 = 2.0;
+const STORM_REFERENCE_HEIGHT: f32 = 20.0;
+const QUAKE_DAMAGE: f32 = 3.0;
+/// Chance every tick that a cable in the area is knocked out
+const STORM_CABLE_CHANCE: f64 = 0.05;
+const QUAKE_CABLE_CHANCE: f64 = 0.08;
+
+/// A meteor hitting the surface at `radians`, hurting tiles
+/// and the player within `radius` (along the surface)
+#[derive(Event, Clone, Copy)]
+pub struct MeteorStrike {
+    pub radians: f32,
+    pub radius: f32,
+    pub damage: f32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DisasterKind {
+    MeteorShower,
+    Storm,
+    Quake,
+}
+
+impl DisasterKind {
+    pub const ALL: [DisasterKind; 3] = [Self::MeteorShower, Self::Storm, Self::Quake];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::MeteorShower => "Meteor shower",
+            Self::Storm => "Storm",
+            Self::Quake => "Quake",
+        }
+    }
+
+    /// How many ticks the disaster lasts
+    fn duration(&self) -> u32 {
+        match self {
+            Self::MeteorShower => 30,
+            Self::Storm => 60,
+            Self::Quake => 10,
+        }
+    }
+
+    /// How many tiles to each side of its center the disaster reaches
+    fn spread(&self) -> usize {
+        match self {
+            Self::MeteorShower => 12,
+            Self::Storm => 25,
+            Self::Quake => 15,
+        }
+    }
+}
+
+/// A disaster that is happening right now
+#[derive(Clone, Copy, Debug)]
+struct ActiveDisaster {
+    kind: DisasterKind,
+    radians: f32,
+    ticks_left: u32,
+}
+
+/// Decides when and where disasters happen. Seeded with
+/// the game seed so that the same world gets the same
+/// disasters.
+#[derive(Resource)]
+pub struct DisasterScheduler {
+    rng: ChaCha8Rng,
+
+    /// Ticks until the next disaster starts
+    next_in: u32,
+    active: Vec<ActiveDisaster>,
+}
+
+impl DisasterScheduler {
+    pub fn new(seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let next_in = rng.gen_range(DISASTER_INTERVAL);
+        Self { rng, next_in, active: Vec::new() }
+    }
+
+    fn setup(mut commands: Commands, game_state: Res<GameState>) -> () {
+        commands.insert_resource(Self::new(game_state.game_seed));
+    }
+
+    /// Starts new disasters and lets the active ones do their damage
+    fn tick(
+        mut commands: Commands,
+        mut scheduler: ResMut<DisasterScheduler>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        cable_q: Query<(Entity, &Cable)>,
+        mut strikes: EventWriter<MeteorStrike>,
+        mut damage_events: EventWriter<DamageTileEvent>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        let DisasterScheduler { rng, next_in, active } = &mut *scheduler;
+
+        *next_in = next_in.saturating_sub(1);
+        if *next_in == 0 {
+            let kind = DisasterKind::ALL[rng.gen_range(0..DisasterKind::ALL.len())];
+            let radians = rng.gen_range(0.0..TAU);
+            active.push(ActiveDisaster { kind, radians, ticks_left: kind.duration() });
+            *next_in = rng.gen_range(DISASTER_INTERVAL);
+
+            logger::log::yellow("disaster", format!("{} at {:.2} rad", kind.display_name(), radians));
+            commands.queue(SpawnInfoText(format!("{} incoming!", kind.display_name())));
+        }
+
+        for disaster in active.iter_mut() {
+            let spread = disaster.kind.spread();
+            let area = planet.numbers_in_radius(planet.radians_to_index(disaster.radians), spread);
+            let tiles = area.iter().filter_map(|index| planet.tiles.get(index));
+
+            match disaster.kind {
+                DisasterKind::MeteorShower => if rng.gen_bool(METEOR_CHANCE) {
+                    let offset = rng.gen_range(-1.0..1.0) * spread as f32 * planet.angular_step();
+                    strikes.send(MeteorStrike {
+                        radians: Planet::normalize_radians(disaster.radians + offset),
+                        radius: METEOR_RADIUS,
+                        damage: METEOR_DAMAGE,
+                    });
+                },
+                DisasterKind::Storm => {
+                    for tile in tiles {
+                        let height = tile.tile_type.collision_height().unwrap_or(0.0);
+                        let damage = STORM_DAMAGE * (height / STORM_REFERENCE_HEIGHT).max(0.5);
+                        damage_events.send(DamageTileEvent { tile_id: tile.tile_id, damage });
+                    }
+                    if rng.gen_bool(STORM_CABLE_CHANCE) { Self::knock_out_cable(&mut commands, rng, &cable_q, &area); }
+                },
+                DisasterKind::Quake => {
+                    for tile in tiles {
+                        damage_events.send(DamageTileEvent { tile_id: tile.tile_id, damage: QUAKE_DAMAGE });
+                    }
+                    if rng.gen_bool(QUAKE_CABLE_CHANCE) { Self::knock_out_cable(&mut commands, rng, &cable_q, &area); }
+                },
+            }
+
+            disaster.ticks_left = disaster.ticks_left.saturating_sub(1);
+        }
+        active.retain(|disaster| disaster.ticks_left > 0);
+    }
+
+    /// Removes a random cable with at least one end in `area`,
+    /// nothing is refunded
+    fn knock_out_cable(
+        commands: &mut Commands,
+        rng: &mut ChaCha8Rng,
+        cable_q: &Query<(Entity, &Cable)>,
+        area: &[usize],
+    ) -> () {
+        let cables: Vec<Entity> = cable_q.iter()
+            .filter(|(_, cable)| area.contains(&cable.start_tile_id) || area.contains(&cable.end_tile_id))
+            .map(|(entity, _)| entity)
+            .collect();
+        if cables.is_empty() { return }
+
+        let entity = cables[rng.gen_range(0..cables.len())];
+        commands.queue(RemoveCableCommand { entity, refund: false });
+        commands.queue(SpawnInfoText("A cable was knocked out".to_string()));
+    }
+
+    /// Meteors damage the tiles they land on and leave a crater,
+    /// everything standing in the crater is moved down with it
+    fn meteor_impacts(
+        mut strikes: EventReader<MeteorStrike>,
+        mut damage_events: EventWriter<DamageTileEvent>,
+        mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+        mut meshes: ResMut<Assets<Mesh>>,
+        mut mesh_q: Query<&mut Mesh2d, Or<(With<PlayerPlanet>, With<MeshMaterial2d<PlanetAtmosphereMaterial>>)>>,
+        mut child_q: Query<(Entity, &mut Transform, &Parent)>,
+    ) -> () {
+        let Ok(mut planet) = planet_q.get_single_mut() else { return };
+        let planet_entity = planet.planet_entity();
+        let mut changed = false;
+
+        for strike in strikes.read() {
+            let reach = (strike.radius / TILE_SIZE).ceil() as usize;
+            for index in planet.numbers_in_radius(planet.radians_to_index(strike.radians), reach) {
+                if !planet.tiles.contains_key(&index) { continue }
+                damage_events.send(DamageTileEvent { tile_id: index, damage: strike.damage });
+            }
+
+            // Remember where the surface was for everything in the crater
+            let width = CRATER_WIDTH / planet.radius();
+            let moved: Vec<(Entity, f32, f32)> = child_q.iter()
+                .filter(|(_, _, parent)| parent.get() == planet_entity)
+                .filter_map(|(entity, transform, _)| {
+                    let radians = transform.translation.y.atan2(transform.translation.x);
+                    let delta = (radians - strike.radians + PI).rem_euclid(TAU) - PI;
+                    (delta.abs() < width).then(|| (entity, radians, planet.surface_radius(radians)))
+                })
+                .collect();
+
+            planet.make_crater(strike.radians, width, CRATER_DEPTH);
+            changed = true;
+
+            for (entity, radians, old_radius) in moved {
+                let Ok((_, mut transform, _)) = child_q.get_mut(entity) else { continue };
+                let offset = planet.surface_radius(radians) - old_radius;
+                transform.translation += Vec3::new(radians.cos(), radians.sin(), 0.0) * offset;
+            }
+        }
+
+        if changed {
+            let mesh = generate_planet_mesh(&mut meshes, &planet.radii);
+            for mut mesh2d in mesh_q.iter_mut() {
+                mesh2d.0 = mesh.clone();
+            }
+        }
+    }
+}
+
+pub struct DisasterPlugin;
+impl Plugin for DisasterPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<MeteorStrike>()
+            .add_systems(OnEnter(AppState::Loading), DisasterScheduler::setup.after(Planet::setup))
+            .add_systems(FixedUpdate, DisasterScheduler::tick.in_set(GameTickSet))
+            .add_systems(Update, DisasterScheduler::meteor_impacts.run_if(in_state(AppState::InGame)));
+    }
+}
@@ -3,3 +3,4 @@ pub use planet::*;
 
 pub mod mesh;
 pub mod debug;
+pub mod disaster;
@@ -6,7 +6,7 @@ use bevy_inspector_egui::quick::ResourceInspectorPlugin;
 use noise::{NoiseFn, Perlin};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use crate::{camera::{post_processing::PostProcessSettings, CameraPlugin, CameraSettings, OuterCamera}, components::{foliage::{grass::Grass, rock::Rock, Foliage}, poi::{self, copper::Copper, flag::flag::{Flag, SpawnFlag}, stone::Stone, tree::Tree, PointOfInterest, PointOfInterestType}, tile::{spawn::SpawnTileCommand, types::landed_rocket::LandedRocket, Tile, TileType, TILE_SIZE}}, systems::{game::{GameState, PlanetResources}, traits::{GenericPointOfInterest, GenericTile}}, utils::{audio::PlayAudioEvent, color::hex}, RES_WIDTH};
+use crate::{camera::{post_processing::PostProcessSettings, CameraPlugin, CameraSettings, OuterCamera}, components::{cable::{slot_state::order, tier::CableTier}, foliage::{grass::Grass, rock::Rock, Foliage}, poi::{self, copper::Copper, flag::flag::{Flag, SpawnFlag}, stone::Stone, tree::Tree, PointOfInterest, PointOfInterestType}, tile::{spawn::SpawnTileCommand, types::landed_rocket::LandedRocket, Tile, TileType, TILE_SIZE}}, systems::{game::{AppState, GameState, GameTickSet, PlanetResources, Research}, traits::{GenericPointOfInterest, GenericTile}}, utils::{audio::PlayAudioEvent, color::hex, logger}, RES_WIDTH};
 use super::{debug::{self, PlanetConfiguration}, mesh::generate_planet_mesh};
 
 /* Constants */
@@ -16,6 +16,8 @@ const PLANET_SHADER_PATH: &str = "shaders/planet.wgsl";
 const PLANET_ATMOSPHERE_SHADER_PATH: &str = "shaders/planet_atmosphere.wgsl";
 const CAMERA_ELEVATION: f32 = 50.0;
 const CAMERA_DAMPING: f32 = 1.0; // 1 = no damping 2 = pretty smooth, less than 1 = do not
+/// How many game ticks one full day (and night) lasts
+const DAY_LENGTH_TICKS: f32 = 600.0;
 
 #[derive(Asset, TypePath, AsBindGroup, Debug, Clone)]
 pub struct PlanetMaterial {
@@ -71,6 +73,9 @@ pub struct Planet {
     /// TODO: Maybe move this to a player instead?
     pub resources: PlanetResources,
 
+    /// What has been researched, see [`Research`]
+    pub research: Research,
+
     /// The entity of the planet, used for e.g getting
     /// the center of the planets (transforms) and such.
     /// TODO: NO OPTION _  TEMP ENTITY INSTEAD
@@ -86,10 +91,27 @@ pub struct Planet {
     pub amplitude: f32,
     pub frequency: f64,
     pub resolution: usize,
+    pub resource_abundance: f32,
 
     /// The planets radii
     /// Vec<(angle, radius or height)>
     pub radii: Vec<(f32, f32)>,
+
+    /// At what angle the sun is right above the surface.
+    /// Everything within a quarter turn of it has daylight.
+    pub sun_radians: f32,
+
+    /// What tier of cable connects two tiles, keyed by
+    /// `order(a, b)`. Connections without an entry are basic.
+    pub cable_tiers: HashMap<(usize, usize), CableTier>,
+
+    /// How much energy passed through each cable during
+    /// the last tick, keyed by `order(a, b)`.
+    pub cable_flow: HashMap<(usize, usize), f32>,
+
+    /// All energy generators have put out since
+    /// the planet was created
+    pub energy_generated: f32,
 }
 
 impl Default for Planet {
@@ -99,13 +121,19 @@ impl Default for Planet {
             points_of_interest: HashMap::new(),
             tiles: HashMap::new(),
             resources: PlanetResources::default(),
+            research: Research::default(),
             planet_entity: None,
             amplitude: 2000.0,
             frequency: 80.0,
             resolution: 500,
+            resource_abundance: 1.0,
             radius: 1400.0,
             radii: Vec::new(),
             seed: 0,
+            sun_radians: 0.0,
+            cable_tiers: HashMap::new(),
+            cable_flow: HashMap::new(),
+            energy_generated: 0.0,
         }
     }
 }
@@ -166,13 +194,19 @@ impl Planet {
             points_of_interest: HashMap::new(),
             tiles: HashMap::new(),
             resources: PlanetResources::default(),
+            research: Research::default(),
             planet_entity: Some(planet_bundle.id()),
             amplitude: config.amplitude,
             frequency: config.frequency,
             resolution: config.resolution,
+            resource_abundance: config.resource_abundance,
             radius,
             radii,
             seed,
+            sun_radians: 0.0,
+            cable_tiers: HashMap::new(),
+            cable_flow: HashMap::new(),
+            energy_generated: 0.0,
         };
         planet_bundle.insert(PlayerPlanet); // TODO: Only insert if it's the players own
 
@@ -395,6 +429,54 @@ impl Planet {
         let counterclockwise_distance = (number + self.tile_places() - position_index) % self.tile_places();
         clockwise_distance <= radius || counterclockwise_distance <= radius
     }
+    /// How many tiles apart two position indices are, the
+    /// shortest way around the planet
+    pub fn index_distance(&self, a: usize, b: usize) -> usize {
+        let distance = a.abs_diff(b) % self.tile_places();
+        distance.min(self.tile_places() - distance)
+    }
+
+    /// How tall the tiles and POI:s covering `index` are for the
+    /// player, 0.0 if nothing solid is there. Tiles and POI:s cover
+    /// as many indices as they are wide.
+    pub fn collision_height_at(&self, index: usize) -> f32 {
+        let mut height: f32 = 0.0;
+
+        for i in self.numbers_in_radius(index, 5) {
+            if let Some(tile) = self.tiles.get(&i) {
+                if let Some(tile_height) = tile.tile_type.collision_height() {
+                    if Tile::get_tile_spread(tile.width(), i, self.tile_places()).contains(&index) {
+                        height = height.max(tile_height);
+                    }
+                }
+            }
+
+            for poi in self.points_of_interest.get(&i).into_iter().flatten() {
+                let Some(poi_height) = poi.poi_type.collision_height() else { continue };
+                if Tile::get_tile_spread(poi.poi_type.width(), i, self.tile_places()).contains(&index) {
+                    height = height.max(poi_height);
+                }
+            }
+        }
+
+        height
+    }
+
+    /// Distance from the planet center to the surface at `radians`
+    pub fn surface_radius(&self, radians: f32) -> f32 {
+        self.radians_to_radii(radians, 0.0).0.length()
+    }
+
+    /// Lowers the surface around `radians` by up to `depth`,
+    /// smoothly over `width` radians to each side. The planet
+    /// mesh has to be regenerated afterwards.
+    pub fn make_crater(&mut self, radians: f32, width: f32, depth: f32) -> () {
+        for (angle, height) in self.radii.iter_mut() {
+            let delta = (*angle - radians + PI).rem_euclid(TAU) - PI;
+            if delta.abs() >= width { continue }
+            *height -= depth * ((PI * delta / width).cos() + 1.0) / 2.0;
+        }
+    }
 
     /// Jag kan inte förklara denna på engelska. Men den ger tillbaka en Vec3
     /// som man kan multiplicera med ett värde, exempelvis 5.0, vilket ger tillbaka
@@ -418,7 +500,7 @@ impl Planet {
             .add_type(PointOfInterestType::Copper(Copper), 0.3)
             .with_origin_offset(-15.0)
             .with_z_index(-1.5)
-            .with_probability(0.3)
+            .with_probability((0.3 * self.resource_abundance).min(1.0))
             .with_local_seed(1)
             .spawn_all(commands, asset_server, self);
 
@@ -426,7 +508,7 @@ impl Planet {
             .add_type(PointOfInterestType::Tree(Tree::new()), 1.0)
             .with_origin_offset(-1.0)
             .with_z_index(-2.0)
-            .with_probability(0.4)
+            .with_probability((0.4 * self.resource_abundance).min(1.0))
             .with_local_seed(0)
             .spawn_all(commands, asset_server, self);
     }
@@ -442,6 +524,44 @@ impl Planet {
         if let Some(e) = self.tiles.get_mut(&a) { e.powergrid_status_mut().connected_tiles.push(b); }
         if let Some(e) = self.tiles.get_mut(&b) { e.powergrid_status_mut().connected_tiles.push(a); }
     }
+    pub fn powergrid_register_cable(&mut self, a: usize, b: usize, tier: CableTier) -> () {
+        self.powergrid_register_connection(a, b);
+        self.cable_tiers.insert(order(a, b), tier);
+    }
+    pub fn powergrid_unregister_connection(&mut self, a: usize, b: usize) -> () {
+        if let Some(e) = self.tiles.get_mut(&a) { e.powergrid_status_mut().connected_tiles.retain(|&id| id != b); }
+        if let Some(e) = self.tiles.get_mut(&b) { e.powergrid_status_mut().connected_tiles.retain(|&id| id != a); }
+        self.cable_tiers.remove(&order(a, b));
+    }
+    pub fn cable_tier(&self, a: usize, b: usize) -> CableTier {
+        self.cable_tiers.get(&order(a, b)).cloned().unwrap_or_default()
+    }
+
+    /// All tiles that energy can reach from `tile_id` (including
+    /// itself). Stops at tiles that don't conduct, like open switches.
+    pub fn powergrid_network(&self, tile_id: usize) -> Vec<usize> {
+        let mut network = vec![tile_id];
+        let mut i = 0;
+
+        while let Some(current) = network.get(i).cloned() {
+            i += 1;
+            let Some(tile) = self.tiles.get(&current) else { continue };
+            if current != tile_id && !tile.powergrid_status.conducting { continue }
+
+            for next in &tile.powergrid_status.connected_tiles {
+                if !network.contains(next) { network.push(*next); }
+            }
+        }
+
+        network
+    }
+
+    /// If the sun is shining on the position index
+    pub fn is_daylight(&self, position_index: usize) -> bool {
+        let radians = position_index as f32 * self.angular_step();
+        let delta = (radians - self.sun_radians + PI).rem_euclid(TAU) - PI;
+        delta.abs() < PI / 2.0
+    }
 }
 
 #[derive(Resource, Default)]
@@ -460,9 +580,9 @@ impl Plugin for PlanetPlugin {
             .init_resource::<PlanetConfiguration>()
             .register_type::<PlanetConfiguration>()
             .add_plugins(ResourceInspectorPlugin::<PlanetConfiguration>::default())
-            .add_systems(Startup, Planet::setup)
-            .add_systems(Update, (Planet::update, debug::on_update))
-            .add_systems(FixedUpdate, Self::tick);
+            .add_systems(OnEnter(AppState::Loading), Planet::setup)
+            .add_systems(Update, (Planet::update, debug::on_update.run_if(in_state(AppState::InGame))))
+            .add_systems(FixedUpdate, Self::tick.in_set(GameTickSet));
     }
 }
 
@@ -472,18 +592,47 @@ impl PlanetPlugin {
         for mut planet in planets.iter_mut() {
             let mut energy_to_add: HashMap<usize, f32> = HashMap::new();
             let tile_keys = planet.tiles.keys().cloned().collect::<Vec<usize>>();
+
+            // Tiles that can't cover their consumption are unpowered
+            for key in &tile_keys {
+                let Some(tile) = planet.tiles.get_mut(key) else { continue };
+                let consumption = tile.energy_consumption();
+                tile.powergrid_status.powered = consumption
+                    .map_or(true, |consumption| tile.powergrid_status.energy_stored >= consumption);
+                tile.powergrid_status.energy_received = 0.0;
+                tile.powergrid_status.overloaded = false;
+            }
+
+            // Broken tiles don't do anything until repaired
             for key in &tile_keys {
+                if planet.tiles[key].is_broken() { continue }
                 let tile_type = planet.tiles[key].tile_type.clone();
                 tile_type.on_tick(*key, &mut planet, &mut audio_events);
             }
 
+            // What consumers want from the grid, after they've used
+            // their energy for this tick
+            for key in &tile_keys {
+                let Some(tile) = planet.tiles.get_mut(key) else { continue };
+                let free_capacity = tile.energy_capacity() - tile.powergrid_status.energy_stored;
+                let demand = tile.energy_consumption()
+                    .map_or(0.0, |consumption| consumption.min(free_capacity).max(0.0));
+                tile.powergrid_status.energy_demanded = if tile.is_broken() { 0.0 } else { demand };
+            }
+
+            let mut energy_through: HashMap<usize, f32> = HashMap::new();
+            let mut cable_flow: HashMap<(usize, usize), f32> = HashMap::new();
             for key in tile_keys {
                 let tile = planet.tiles.get(&key).unwrap();
                 if let Some(energy_output) = tile.energy_output() {
+                    let tile_id = tile.tile_id;
+                    planet.energy_generated += energy_output;
                     Tile::distribute_energy_from(
-                        tile.tile_id,
+                        tile_id,
                         energy_output,
                         &mut energy_to_add,
+                        &mut energy_through,
+                        &mut cable_flow,
                         &mut planet
                     );
                 }
@@ -491,8 +640,38 @@ impl PlanetPlugin {
 
             // Apply the energy to tiles
             for (tile_id, energy) in energy_to_add {
+                if let Some(tile) = planet.tiles.get_mut(&tile_id) {
+                    tile.powergrid_status.energy_received = energy;
+                }
                 Tile::add_energy(&mut planet, tile_id, energy);
             }
+
+            // Cables running at their limit overload both ends
+            for (&(a, b), &flow) in cable_flow.iter() {
+                if flow < planet.cable_tier(a, b).max_throughput() { continue }
+                for tile_id in [a, b] {
+                    if let Some(tile) = planet.tiles.get_mut(&tile_id) {
+                        tile.powergrid_status.overloaded = true;
+                    }
+                }
+            }
+            planet.cable_flow = cable_flow;
+
+            // Trip tiles that had too much energy passing through
+            for (tile_id, energy) in energy_through {
+                let Some(tile) = planet.tiles.get_mut(&tile_id) else { continue };
+                let Some(max_throughput) = tile.tile_type.max_throughput() else { continue };
+                if energy > max_throughput {
+                    tile.powergrid_status.overloaded = true;
+                }
+                if energy > max_throughput && tile.powergrid_status.conducting {
+                    tile.powergrid_status.conducting = false;
+                    logger::log::yellow("powergrid", format!("{} at {} tripped ({energy:.1}/{max_throughput:.1})",
+                        tile.tile_type.display_name(), tile_id));
+                }
+            }
+
+            planet.sun_radians = Planet::normalize_radians(planet.sun_radians + TAU / DAY_LENGTH_TICKS);
         }
     }
 }
@@ -0,0 +1,193 @@
+/* Imports */
+use std::f32::consts::{PI, TAU};
+use bevy::prelude::*;
+use crate::{
+    components::{planet::{disaster::MeteorStrike, Planet, PlayerPlanet}, poi::flag::flag::Flag, tile::TileType},
+    systems::game::{AppState, GameTickSet},
+    ui::info_text::SpawnInfoText,
+};
+use super::{player::Player, tool::PLAYER_MAX_ENERGY};
+
+/* Constants */
+pub const PLAYER_MAX_HEALTH: f32 = 100.0;
+/// Landing slower than this doesn't hurt (units / s)
+const SAFE_FALL_SPEED: f32 = 160.0;
+/// Damage for every unit / s the landing speed is above `SAFE_FALL_SPEED`
+const FALL_DAMAGE: f32 = 0.6;
+/// How many tiles away from an overloaded tile the player gets hurt
+const OVERLOAD_RANGE: usize = 2;
+/// Damage every tick while standing next to an overloaded tile
+const OVERLOAD_DAMAGE: f32 = 4.0;
+/// Health regained every tick while not taking damage
+const REGENERATION: f32 = 0.5;
+/// Seconds until a dead player respawns
+const RESPAWN_DELAY: f32 = 3.0;
+
+/// What hurt the player
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DamageSource {
+    Fall,
+    Meteor,
+    Overload,
+    Creature,
+}
+
+impl DamageSource {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Fall => "Fall",
+            Self::Meteor => "Meteor",
+            Self::Overload => "Overloaded grid",
+            Self::Creature => "Creature",
+        }
+    }
+}
+
+/// Sent to hurt the player
+#[derive(Event)]
+pub struct PlayerDamageEvent {
+    pub amount: f32,
+    pub source: DamageSource,
+}
+
+/// Health of the player, which respawns at the landed
+/// rocket (or the flag) some time after dying
+#[derive(Component)]
+pub struct PlayerHealth {
+    pub health: f32,
+
+    /// Seconds left until respawning, `Some` while dead
+    respawn_in: Option<f32>,
+
+    /// If the player was hurt since the last tick,
+    /// health only regenerates when it wasn't
+    hurt: bool,
+}
+
+impl Default for PlayerHealth {
+    fn default() -> Self {
+        Self { health: PLAYER_MAX_HEALTH, respawn_in: None, hurt: false }
+    }
+}
+
+impl PlayerHealth {
+    pub fn is_dead(&self) -> bool {
+        self.respawn_in.is_some()
+    }
+
+    /// Run condition for things the player can't do while dead
+    pub fn alive(health_q: Query<&PlayerHealth>) -> bool {
+        health_q.iter().all(|health| !health.is_dead())
+    }
+
+    /// How much damage landing at `speed` (positive, towards
+    /// the planet) does
+    pub fn fall_damage(speed: f32) -> f32 {
+        (speed - SAFE_FALL_SPEED).max(0.0) * FALL_DAMAGE
+    }
+
+    fn apply_damage(
+        mut commands: Commands,
+        mut events: EventReader<PlayerDamageEvent>,
+        mut player_q: Query<(&mut PlayerHealth, &mut Visibility), With<Player>>,
+    ) -> () {
+        for event in events.read() {
+            for (mut health, mut visibility) in player_q.iter_mut() {
+                if health.is_dead() || event.amount <= 0.0 { continue }
+                health.health = (health.health - event.amount).max(0.0);
+                health.hurt = true;
+
+                if health.health <= 0.0 {
+                    health.respawn_in = Some(RESPAWN_DELAY);
+                    *visibility = Visibility::Hidden;
+                    commands.queue(SpawnInfoText(format!("You died ({})",
+                        event.source.display_name().to_lowercase())));
+                }
+            }
+        }
+    }
+
+    /// Meteors hurt the player if they land close enough
+    fn meteor_strikes(
+        mut strikes: EventReader<MeteorStrike>,
+        mut damage_events: EventWriter<PlayerDamageEvent>,
+        player_q: Query<&Player>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        for strike in strikes.read() {
+            for player in player_q.iter() {
+                let angle = (strike.radians - player.radians + PI).rem_euclid(TAU) - PI;
+                if angle.abs() * planet.radius() > strike.radius { continue }
+                damage_events.send(PlayerDamageEvent { amount: strike.damage, source: DamageSource::Meteor });
+            }
+        }
+    }
+
+    /// Overloaded tiles hurt the player when it's close, and
+    /// health slowly comes back otherwise
+    fn tick(
+        mut damage_events: EventWriter<PlayerDamageEvent>,
+        mut player_q: Query<(&Player, &mut PlayerHealth)>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        for (player, mut health) in player_q.iter_mut() {
+            if health.is_dead() { continue }
+
+            let player_index = planet.radians_to_index(player.radians);
+            let overloaded = planet.numbers_in_radius(player_index, OVERLOAD_RANGE).iter()
+                .any(|index| planet.tiles.get(index).is_some_and(|tile| tile.powergrid_status.overloaded));
+
+            if overloaded {
+                damage_events.send(PlayerDamageEvent { amount: OVERLOAD_DAMAGE, source: DamageSource::Overload });
+            } else if !health.hurt && health.health < PLAYER_MAX_HEALTH {
+                health.health = (health.health + REGENERATION).min(PLAYER_MAX_HEALTH);
+            }
+            health.hurt = false;
+        }
+    }
+
+    /// Brings the player back at the landed rocket, or
+    /// at the flag if there is none
+    fn respawn(
+        mut commands: Commands,
+        time: Res<Time>,
+        mut player_q: Query<(&mut Player, &mut PlayerHealth, &mut Visibility)>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        flag_q: Query<&GlobalTransform, With<Flag>>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        for (mut player, mut health, mut visibility) in player_q.iter_mut() {
+            let Some(respawn_in) = health.respawn_in.as_mut() else { continue };
+            *respawn_in -= time.delta_secs();
+            if *respawn_in > 0.0 { continue }
+
+            let rocket = planet.tiles.values()
+                .find(|tile| matches!(tile.tile_type, TileType::LandedRocket(_)))
+                .map(|tile| tile.tile_id as f32 * planet.angular_step());
+            let flag = flag_q.iter().next()
+                .map(|transform| transform.translation().y.atan2(transform.translation().x));
+
+            player.radians = Planet::normalize_radians(rocket.or(flag).unwrap_or(0.0));
+            player.ground = planet.collision_height_at(planet.radians_to_index(player.radians));
+            player.altitude = player.ground;
+            player.vertical_velocity = 0.0;
+            player.energy = PLAYER_MAX_ENERGY;
+
+            *health = PlayerHealth::default();
+            *visibility = Visibility::Inherited;
+            commands.queue(SpawnInfoText("You respawned".to_string()));
+        }
+    }
+}
+
+pub struct HealthPlugin;
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<PlayerDamageEvent>()
+            .add_systems(Update, (PlayerHealth::meteor_strikes, PlayerHealth::apply_damage, PlayerHealth::respawn).chain().run_if(in_state(AppState::InGame)))
+            .add_systems(FixedUpdate, PlayerHealth::tick.in_set(GameTickSet));
+    }
+}
@@ -1 +1,3 @@
 pub mod player;
+pub mod tool;
+pub mod health;
@@ -2,15 +2,40 @@ use std::time::Duration;
 
 /* Imports */
 use bevy::{prelude::*, sprite::Anchor};
-use crate::{components::planet::{self, Planet, PlayerPlanet}, utils::color::hex};
+use crate::{components::planet::{self, Planet, PlayerPlanet}, systems::game::AppState, utils::color::hex};
+use super::{health::{DamageSource, HealthPlugin, PlayerDamageEvent, PlayerHealth}, tool::{ToolBelt, ToolPlugin, PLAYER_MAX_ENERGY}};
 
 /* Constants */
+/// Pulls the player towards the planet center (units / s²)
+const GRAVITY: f32 = 300.0;
+const JUMP_SPEED: f32 = 110.0;
+/// Upwards acceleration while the jetpack is firing (units / s²)
+const JETPACK_ACCELERATION: f32 = 520.0;
+/// Portable energy used by the jetpack every second
+const JETPACK_DRAIN: f32 = 12.0;
+const MAX_FALL_SPEED: f32 = 250.0;
+const MAX_ALTITUDE: f32 = 250.0;
+/// Obstacles this much taller than where the player
+/// stands are walked over, taller ones block
+const STEP_HEIGHT: f32 = 6.0;
 
 /// Player component
 #[derive(Component)]
 pub struct Player {
     pub radians: f32,
     pub speed: f32,
+
+    /// Portable energy used by tools, recharged
+    /// by standing close to a battery
+    pub energy: f32,
+
+    /// Height above the planet surface, 0.0 = on the ground
+    pub altitude: f32,
+    /// Positive is away from the planet center
+    pub vertical_velocity: f32,
+
+    /// Altitude of whatever the player is above (tiles, rocks)
+    pub ground: f32,
 }
 
 
@@ -37,7 +62,9 @@ impl Player {
         let texture_atlas_layout = texture_atlas_layout.add(layout);
 
         commands.spawn((
-            Player { radians: 0.0, speed: 10.0, },
+            Player { radians: 0.0, speed: 10.0, energy: PLAYER_MAX_ENERGY, altitude: 0.0, vertical_velocity: 0.0, ground: 0.0 },
+            ToolBelt::default(),
+            PlayerHealth::default(),
             Sprite {
                 texture_atlas: Some(TextureAtlas {
                     index: 0,
@@ -60,6 +87,7 @@ impl Player {
         mut player_q: Query<(&mut Transform, &mut Player, &mut Sprite, &RunAnimationIndices, &mut RunAnimationTimer), With<Player>>,
         planet_q: Query<&Planet, With<PlayerPlanet>>,
         time: Res<Time>,
+        mut damage_events: EventWriter<PlayerDamageEvent>,
     ) -> () {
         let planet = planet_q.single(); 
 
@@ -80,32 +108,42 @@ impl Player {
         let mut backwards = false;
 
         if kb.pressed(KeyCode::KeyA) {
-            for (mut transform, mut player, mut sprite, _, _) in player_q.iter_mut() {
-                player.radians += player.speed / 10000.0;
-
-                let new_transform = planet.radians_to_transform(player.radians, 0.0, 10.0);
-                transform.translation = new_transform.translation;
-                transform.rotation = new_transform.rotation;
-                
+            for (_, mut player, _, _, _) in player_q.iter_mut() {
+                let delta = player.speed / 10000.0;
+                Self::walk(&mut player, planet, delta);
                 // sprite.flip_x = true;
             }
 
             backwards = true;
         }
         if kb.pressed(KeyCode::KeyD) {
-            for (mut transform, mut player, mut sprite, _, _) in player_q.iter_mut() {
-                player.radians -= player.speed / 10000.0;
-
-                let new_transform = planet.radians_to_transform(player.radians, 0.0, 10.0);
-                transform.translation = new_transform.translation;
-                transform.rotation = new_transform.rotation;
-
+            for (_, mut player, _, _, _) in player_q.iter_mut() {
+                let delta = -player.speed / 10000.0;
+                Self::walk(&mut player, planet, delta);
                 // sprite.flip_x = false;
             }
 
             backwards = false;
         }
-        if kb.pressed(KeyCode::KeyD) ^ kb.pressed(KeyCode::KeyA) {
+
+        for (mut transform, mut player, _, _, _) in player_q.iter_mut() {
+            player.ground = planet.collision_height_at(planet.radians_to_index(player.radians));
+            if let Some(impact_speed) = Self::apply_vertical_motion(&mut player, &kb, time.delta_secs()) {
+                let amount = PlayerHealth::fall_damage(impact_speed);
+                if amount > 0.0 {
+                    damage_events.send(PlayerDamageEvent { amount, source: DamageSource::Fall });
+                }
+            }
+
+            // The altitude is relative to the surface, so the
+            // player follows slopes when walking
+            let new_transform = planet.radians_to_transform(player.radians, player.altitude, 10.0);
+            transform.translation = new_transform.translation;
+            transform.rotation = new_transform.rotation;
+        }
+
+        if (kb.pressed(KeyCode::KeyD) ^ kb.pressed(KeyCode::KeyA))
+            && player_q.iter().all(|(_, player, ..)| player.is_grounded()) {
             
             Self::animate_run(time, player_q, backwards);
         }
@@ -119,6 +157,47 @@ impl Player {
         // }
     }
 
+    pub fn is_grounded(&self) -> bool {
+        self.altitude <= self.ground
+    }
+
+    /// Moves the player along the surface, unless
+    /// something too tall is in the way
+    fn walk(player: &mut Player, planet: &Planet, delta_radians: f32) -> () {
+        let radians = player.radians + delta_radians;
+        let obstacle = planet.collision_height_at(planet.radians_to_index(radians));
+        if obstacle > player.altitude + STEP_HEIGHT { return }
+        player.radians = radians;
+    }
+
+    /// Space jumps when on the ground, and fires the
+    /// jetpack while in the air if there's energy left.
+    /// Returns how fast the player was falling if it landed.
+    fn apply_vertical_motion(player: &mut Player, kb: &ButtonInput<KeyCode>, dt: f32) -> Option<f32> {
+        if player.is_grounded() && kb.just_pressed(KeyCode::Space) {
+            player.vertical_velocity = JUMP_SPEED;
+        } else if !player.is_grounded() && kb.pressed(KeyCode::Space) && player.energy > 0.0 {
+            player.vertical_velocity += JETPACK_ACCELERATION * dt;
+            player.energy = (player.energy - JETPACK_DRAIN * dt).max(0.0);
+        }
+
+        player.vertical_velocity = (player.vertical_velocity - GRAVITY * dt).max(-MAX_FALL_SPEED);
+        player.altitude += player.vertical_velocity * dt;
+
+        if player.altitude >= MAX_ALTITUDE {
+            player.altitude = MAX_ALTITUDE;
+            player.vertical_velocity = player.vertical_velocity.min(0.0);
+        }
+        // Land on (or step up onto) the ground
+        if player.altitude <= player.ground {
+            let impact_speed = -player.vertical_velocity;
+            player.altitude = player.ground;
+            player.vertical_velocity = player.vertical_velocity.max(0.0);
+            if impact_speed > 0.0 { return Some(impact_speed) }
+        }
+        None
+    }
+
     fn animate_run(
         time: Res<Time>,
         mut player_q: Query<(
@@ -164,7 +243,8 @@ pub struct PlayerPlugin;
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
         app
-            .add_systems(Startup, Player::setup.after(Planet::setup))
-            .add_systems(Update, Player::update);
+            .add_plugins((ToolPlugin, HealthPlugin))
+            .add_systems(OnEnter(AppState::Loading), Player::setup.after(Planet::setup))
+            .add_systems(Update, Player::update.run_if(PlayerHealth::alive).run_if(in_state(AppState::InGame)));
     }
 }
@@ -0,0 +1,199 @@
+/* Imports */
+use std::f32::consts::{PI, TAU};
+use bevy::prelude::*;
+use crate::{
+    components::{planet::{Planet, PlayerPlanet}, poi::{copper::Copper, stone::Stone, tree::Tree, PointOfInterestType}, tile::TileType},
+    functional::damageable::{DamageType, Resistances},
+    systems::game::{AppState, GameTickSet},
+    ui::info_text::SpawnInfoText,
+};
+use super::player::Player;
+
+/* Constants */
+/// How much portable energy the player can carry
+pub const PLAYER_MAX_ENERGY: f32 = 100.0;
+/// How many tiles away from a battery the player recharges
+const CHARGE_RANGE: usize = 3;
+/// How much energy the player takes from batteries every tick
+const CHARGE_RATE: f32 = 4.0;
+
+/// Something the player hits points of interest with
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Tool {
+    Axe,
+    Pickaxe,
+    EnergyDrill,
+}
+
+impl Tool {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Axe => "Axe",
+            Self::Pickaxe => "Pickaxe",
+            Self::EnergyDrill => "Energy drill",
+        }
+    }
+
+    /// How much damage one hit does, before
+    /// the resistances of the target
+    pub fn damage(&self) -> f32 {
+        match self {
+            Self::Axe => 7.0,
+            Self::Pickaxe => 6.0,
+            Self::EnergyDrill => 10.0,
+        }
+    }
+
+    pub fn damage_type(&self) -> DamageType {
+        match self {
+            Self::Axe => DamageType::Cutting,
+            Self::Pickaxe => DamageType::Blunt,
+            Self::EnergyDrill => DamageType::Energy,
+        }
+    }
+
+    /// How far away (along the surface) the player can hit things
+    pub fn reach(&self) -> f32 {
+        match self {
+            Self::Axe | Self::Pickaxe => 40.0,
+            Self::EnergyDrill => 70.0,
+        }
+    }
+
+    /// Seconds between two hits
+    pub fn cooldown(&self) -> f32 {
+        match self {
+            Self::Axe => 0.5,
+            Self::Pickaxe => 0.7,
+            Self::EnergyDrill => 0.25,
+        }
+    }
+
+    /// Portable energy used for every hit
+    pub fn energy_cost(&self) -> f32 {
+        match self {
+            Self::Axe | Self::Pickaxe => 0.0,
+            Self::EnergyDrill => 3.0,
+        }
+    }
+}
+
+/// The tools of the player, selected with 1-3
+#[derive(Component)]
+pub struct ToolBelt {
+    pub selected: Tool,
+
+    /// Seconds left until the selected tool can be used again
+    cooldown: f32,
+}
+
+impl Default for ToolBelt {
+    fn default() -> Self {
+        Self { selected: Tool::Axe, cooldown: 0.0 }
+    }
+}
+
+impl ToolBelt {
+    /// Uses the selected tool on something at `target_radians` (around
+    /// the planet). Returns the damage of the hit (before resistances),
+    /// or why it didn't work. Nothing happens while the tool is cooling down.
+    pub fn try_use(
+        &mut self,
+        player: &mut Player,
+        target_name: &str,
+        resistances: &Resistances,
+        target_radians: f32,
+        planet: &Planet,
+    ) -> Result<Option<(f32, DamageType)>, String> {
+        if self.cooldown > 0.0 { return Ok(None) }
+        let tool = self.selected;
+
+        let angle = (target_radians - player.radians + PI).rem_euclid(TAU) - PI;
+        if angle.abs() * planet.radius() > tool.reach() {
+            return Err("Too far away".to_string());
+        }
+
+        if resistances.is_immune(tool.damage_type()) {
+            return Err(format!("The {} can't break {}", tool.display_name().to_lowercase(),
+                target_name.to_lowercase()));
+        }
+        if player.energy < tool.energy_cost() {
+            return Err(format!("The {} needs energy", tool.display_name().to_lowercase()));
+        }
+
+        player.energy -= tool.energy_cost();
+        self.cooldown = tool.cooldown();
+        Ok(Some((tool.damage(), tool.damage_type())))
+    }
+
+    /// 1, 2, 3
+    fn select(
+        mut commands: Commands,
+        kb: Res<ButtonInput<KeyCode>>,
+        mut belt_q: Query<&mut ToolBelt>,
+    ) -> () {
+        let tool = if kb.just_pressed(KeyCode::Digit1) { Tool::Axe }
+            else if kb.just_pressed(KeyCode::Digit2) { Tool::Pickaxe }
+            else if kb.just_pressed(KeyCode::Digit3) { Tool::EnergyDrill }
+            else { return };
+
+        for mut belt in belt_q.iter_mut() {
+            if belt.selected == tool { continue }
+            belt.selected = tool;
+            belt.cooldown = 0.0;
+            commands.queue(SpawnInfoText(format!("{} selected ({} damage)", tool.display_name(),
+                tool.damage_type().display_name().to_lowercase())));
+        }
+    }
+
+    fn tick_cooldown(time: Res<Time>, mut belt_q: Query<&mut ToolBelt>) -> () {
+        for mut belt in belt_q.iter_mut() {
+            belt.cooldown = (belt.cooldown - time.delta_secs()).max(0.0);
+        }
+    }
+
+    /// Batteries close to the player recharge its portable energy
+    fn recharge(
+        mut player_q: Query<&mut Player>,
+        mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+    ) -> () {
+        let Ok(mut planet) = planet_q.get_single_mut() else { return };
+        for mut player in player_q.iter_mut() {
+            let mut missing = PLAYER_MAX_ENERGY - player.energy;
+            if missing <= 0.0 { continue }
+
+            let player_index = planet.radians_to_index(player.radians);
+            for index in planet.numbers_in_radius(player_index, CHARGE_RANGE) {
+                let Some(tile) = planet.tiles.get_mut(&index) else { continue };
+                if !matches!(tile.tile_type, TileType::Battery(_)) { continue }
+
+                let amount = CHARGE_RATE.min(missing).min(tile.powergrid_status.energy_stored);
+                tile.powergrid_status.energy_stored -= amount;
+                player.energy += amount;
+                missing -= amount;
+            }
+        }
+    }
+}
+
+/// What kind of POI an entity is, by its marker component
+pub fn poi_type_of(
+    entity: Entity,
+    poi_q: &Query<(Option<&Tree>, Option<&Stone>, Option<&Copper>)>,
+) -> Option<PointOfInterestType> {
+    match poi_q.get(entity).ok()? {
+        (Some(tree), _, _) => Some(PointOfInterestType::Tree(*tree)),
+        (_, Some(stone), _) => Some(PointOfInterestType::Stone(*stone)),
+        (_, _, Some(copper)) => Some(PointOfInterestType::Copper(*copper)),
+        _ => None,
+    }
+}
+
+pub struct ToolPlugin;
+impl Plugin for ToolPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, (ToolBelt::select, ToolBelt::tick_cooldown).run_if(in_state(AppState::InGame)))
+            .add_systems(FixedUpdate, ToolBelt::recharge.in_set(GameTickSet));
+    }
+}
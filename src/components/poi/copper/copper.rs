@@ -2,7 +2,11 @@
 use bevy::{prelude::*, sprite::Anchor};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use crate::systems::traits::GenericPointOfInterest;
+use crate::{
+    components::poi::PointOfInterest,
+    functional::damageable::{Damageable, DamageType, HitMaterial, LootTable, Resistances},
+    systems::{game::PlanetResource, traits::GenericPointOfInterest},
+};
 
 #[derive(Component, Clone, Copy, Debug)]
 pub struct Copper;
@@ -28,9 +32,20 @@ impl GenericPointOfInterest for Copper {
                     ..default()
                 },
                 Copper,
-            ));
+                Damageable::new(35.0, HitMaterial::Stone, PointOfInterest::on_destroyed)
+                .with_resistances(Resistances::none()
+                    .with(DamageType::Cutting, 1.0)
+                    .with(DamageType::Blunt, 0.33)
+                    .with(DamageType::Energy, 0.1))
+                .with_loot(LootTable::new(2)
+                    .with(3, PlanetResource::Copper, 2..=4)
+                    .with(1, PlanetResource::Stone, 1..=3)),
+            ))
+            .observe(Damageable::on_clicked);
         }).id()
     }
+
+    fn collision_height(&self) -> Option<f32> { Some(8.0) }
 }
 
 impl Copper {
@@ -59,6 +59,7 @@ impl Flag {
                     ..Default::default()
                 },
                 flag.transform,
+                Flag,
             )).with_child((
                 Mesh2d(mesh_handle),
                 MeshMaterial2d(material_handle),
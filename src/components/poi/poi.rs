@@ -6,7 +6,7 @@ use noise::{NoiseFn, Perlin};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
 use super::{copper::Copper, flag::flag, stone::Stone, tree::Tree};
-use crate::{components::{cable::slot::RemoveAllCableSlotHighlightsCommand, planet::Planet}, systems::traits::GenericPointOfInterest, utils::color::hex};
+use crate::{components::{cable::slot::RemoveAllCableSlotHighlightsCommand, planet::{Planet, PlayerPlanet}}, systems::traits::GenericPointOfInterest, utils::{color::hex, logger}};
 
 /// Some point of interest on the planet, like a stone or a tree.
 /// POI:s are often something that can be interacted with via e.g
@@ -40,6 +40,16 @@ impl PartialEq for PointOfInterestType {
     }
 }
 
+impl PointOfInterestType {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Stone(_) => "Stone",
+            Self::Copper(_) => "Copper",
+            Self::Tree(_) => "Tree",
+        }
+    }
+}
+
 impl PointOfInterest {
     pub fn new(position_index: usize, poi_type: PointOfInterestType) -> Self {
         Self { position_index, poi_type, entity: Entity::PLACEHOLDER }
@@ -48,6 +58,26 @@ impl PointOfInterest {
         PointOfInterestBuilder::new()
     }
 
+    /// `Damageable` callback for POI:s. The damageable is a child of
+    /// the POI entity, so the whole POI is despawned and removed from
+    /// the planet (so that e.g drills stop counting it).
+    pub fn on_destroyed(world: &mut World, entity: Entity, _position: Vec2) -> () {
+        let poi_entity = world.get::<Parent>(entity).map(|parent| parent.get()).unwrap_or(entity);
+
+        let mut query_state = world.query_filtered::<&mut Planet, With<PlayerPlanet>>();
+        if let Ok(mut planet) = query_state.get_single_mut(world) {
+            for pois in planet.points_of_interest.values_mut() {
+                pois.retain(|poi| poi.entity != poi_entity);
+            }
+            planet.points_of_interest.retain(|_, pois| !pois.is_empty());
+        }
+
+        if let Ok(entity_mut) = world.get_entity_mut(poi_entity) {
+            entity_mut.despawn_recursive();
+        }
+        logger::log::bright_green("poi", format!("Removed POI {}", poi_entity));
+    }
+
     /// Returns a vec of the position indices that a POI will occupy.
     /// `probability` needs to be between 0.0 and 1.0. 
     fn generate_position_indices(planet: &Planet, local_seed: u32, probability: f32) -> Vec<usize> {
@@ -2,7 +2,11 @@
 use bevy::{prelude::*, sprite::Anchor};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use crate::systems::traits::GenericPointOfInterest;
+use crate::{
+    components::poi::PointOfInterest,
+    functional::damageable::{Damageable, DamageType, HitMaterial, LootTable, Resistances},
+    systems::{game::PlanetResource, traits::GenericPointOfInterest},
+};
 
 #[derive(Component, Clone, Copy, Debug)]
 pub struct Stone;
@@ -28,9 +32,18 @@ impl GenericPointOfInterest for Stone {
                     ..default()
                 },
                 Stone,
-            ));
+                Damageable::new(30.0, HitMaterial::Stone, PointOfInterest::on_destroyed)
+                .with_resistances(Resistances::none()
+                    .with(DamageType::Cutting, 0.85))
+                .with_loot(LootTable::new(2)
+                    .with(9, PlanetResource::Stone, 3..=5)
+                    .with(1, PlanetResource::Copper, 1..=2)),
+            ))
+            .observe(Damageable::on_clicked);
         }).id()
     }
+
+    fn collision_height(&self) -> Option<f32> { Some(12.0) }
 }
 
 impl Stone {
@@ -2,7 +2,7 @@
 use bevy::{prelude::*, sprite::Anchor};
 use rand::{Rng, SeedableRng};
 use rand_chacha::ChaCha8Rng;
-use crate::{components::foliage::animation::WindSway, functional::damageable::Damageable, systems::{game::PlanetResource, traits::GenericPointOfInterest}, utils::color::hex};
+use crate::{components::{foliage::animation::WindSway, poi::PointOfInterest}, functional::damageable::{Damageable, DamageType, HitMaterial, LootTable, Resistances}, systems::{game::{GameTickSet, LifetimeStat, PlanetResource, Profile}, traits::GenericPointOfInterest}, utils::color::hex};
 
 /* Constants */
 const MAX_TREE_AGE: u8 = 3;
@@ -39,11 +39,14 @@ impl GenericPointOfInterest for Tree {
                 WindSway::new(),
                 Damageable::new(
                     20.0,
-                    Some((PlanetResource::Wood, rng.gen_range(8..15))),
-                    |w: &mut World| {
-                        w.run_system_cached(Self::callback).unwrap();
-                    }
-                ),
+                    HitMaterial::Wood,
+                    Tree::on_felled,
+                )
+                .with_resistances(Resistances::none()
+                    .with(DamageType::Blunt, 0.7)
+                    .with(DamageType::Energy, 0.5))
+                .with_loot(LootTable::new(1)
+                    .with(1, PlanetResource::Wood, 8..=14)),
             ))
             .observe(Damageable::on_clicked);
         }).id()
@@ -57,14 +60,19 @@ impl Tree {
     fn texture(age: u8) -> String {
         format!("foliage/birch/0{}.png", age)
     }
-    fn callback(mut _commands: Commands, _query: Query<&Transform>) -> () {
-    }
 
     /// Increase age
     fn increase_age(&mut self) -> () {
         self.age = (self.age + 1).min(MAX_TREE_AGE);
     }
 
+    /// Counts the tree in the profile, then
+    /// removes it like any other POI
+    fn on_felled(world: &mut World, entity: Entity, position: Vec2) -> () {
+        world.resource_mut::<Profile>().add(LifetimeStat::TreesFelled, 1.0);
+        PointOfInterest::on_destroyed(world, entity, position);
+    }
+
     /// Every game tick
     fn tick(
         mut query: Query<(&mut Tree, &mut Sprite)>,
@@ -82,6 +90,6 @@ impl Tree {
 pub struct TreePlugin;
 impl Plugin for TreePlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(FixedUpdate, Tree::tick);
+        app.add_systems(FixedUpdate, Tree::tick.in_set(GameTickSet));
     }
 }
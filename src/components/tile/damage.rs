@@ -0,0 +1,179 @@
+/* Imports */
+use bevy::{prelude::*, sprite::{Anchor, SpriteImageMode}, text::FontSmoothing, utils::HashSet};
+use crate::{
+    components::planet::{Planet, PlayerPlanet},
+    functional::damageable::{AnimatedDamageText, DamageLabels, Flashing},
+    systems::traits::GenericTile,
+    ui::info_text::SpawnInfoText,
+    utils::{color::hex, logger},
+};
+use super::{Tile, TILE_SIZE};
+
+/* Constants */
+/// Below this fraction of max health tiles show cracks
+const DAMAGED_THRESHOLD: f32 = 0.5;
+/// How far above the tile the broken text is
+const BROKEN_TEXT_ELEVATION: f32 = 12.0;
+
+/// Sent to hurt a tile, e.g by disasters. Tiles
+/// that run out of health break.
+#[derive(Event, Clone, Copy)]
+pub struct DamageTileEvent {
+    pub tile_id: usize,
+    pub damage: f32,
+}
+
+/// Brings a tile back to full health if
+/// the repair cost can be paid
+#[derive(Clone)]
+pub struct RepairTileCommand {
+    pub tile_id: usize,
+}
+
+impl Command for RepairTileCommand {
+    fn apply(self, world: &mut World) {
+        let mut query_state = world.query_filtered::<&mut Planet, With<PlayerPlanet>>();
+        let Ok(mut planet) = query_state.get_single_mut(world) else { return };
+        let Some(tile) = planet.tiles.get(&self.tile_id) else { return };
+        let (cost, max_health, name) = (tile.repair_cost(), tile.max_health(), tile.tile_type.display_name());
+        if cost.is_empty() { return }
+
+        if let Err(e) = planet.resources.try_spend(&cost) {
+            logger::log::bright_red("tile_repair", &e);
+            world.commands().queue(SpawnInfoText(e));
+            return;
+        }
+
+        if let Some(tile) = planet.tiles.get_mut(&self.tile_id) {
+            tile.health = max_health;
+        }
+        logger::log::bright_green("tile_repair", &format!("Repaired {} at index {}", name, self.tile_id));
+    }
+}
+
+pub struct TileDamagePlugin;
+impl Plugin for TileDamagePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_event::<DamageTileEvent>()
+            .add_systems(Update, (Self::apply_damage, DamagedOverlay::update).chain());
+    }
+}
+
+impl TileDamagePlugin {
+    /// Tiles that run out of health break, see [`Tile::is_broken`]
+    fn apply_damage(
+        mut commands: Commands,
+        mut events: EventReader<DamageTileEvent>,
+        mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+        asset_server: Res<AssetServer>,
+        mut damage_labels: ResMut<DamageLabels>,
+        mut damage_text_q: Query<&mut AnimatedDamageText>,
+    ) -> () {
+        let Ok(mut planet) = planet_q.get_single_mut() else { return };
+
+        for event in events.read() {
+            let Some(tile) = planet.tiles.get_mut(&event.tile_id) else { continue };
+            if tile.tile_type.indestructible() || tile.is_broken() { continue }
+            tile.wear(event.damage);
+
+            /* Visual */
+            commands.entity(tile.entity).insert_if_new(Flashing::new());
+            AnimatedDamageText::spawn_damage_text(
+                &mut commands,
+                &asset_server,
+                &mut damage_labels,
+                &mut damage_text_q,
+                tile.entity,
+                event.damage.round().max(1.0),
+            );
+        }
+    }
+}
+
+/// Cracks shown over badly damaged tiles, with
+/// a text on top when they're broken
+#[derive(Component)]
+pub struct DamagedOverlay {
+    tile_id: usize,
+    broken: bool,
+}
+
+impl DamagedOverlay {
+    /// Makes sure that only damaged tiles have an overlay, and
+    /// that it shows if they're broken. Tiles are respawned when
+    /// upgraded, so we also check that it has the right parent.
+    fn update(
+        mut commands: Commands,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        overlay_q: Query<(Entity, &DamagedOverlay, &Parent)>,
+        asset_server: Res<AssetServer>,
+        mut broken_tiles: Local<HashSet<usize>>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        let mut existing: HashSet<usize> = HashSet::new();
+
+        for (entity, overlay, parent) in overlay_q.iter() {
+            let still_needed = planet.tiles.get(&overlay.tile_id).is_some_and(|tile| Self::is_damaged(tile)
+                && tile.is_broken() == overlay.broken
+                && tile.entity == parent.get());
+
+            if still_needed {
+                existing.insert(overlay.tile_id);
+            } else {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        for tile in planet.tiles.values() {
+            if tile.is_broken() && !broken_tiles.contains(&tile.tile_id) {
+                logger::log::yellow("tile_damage", format!("{} at {} broke down", tile.tile_type.display_name(), tile.tile_id));
+                commands.queue(SpawnInfoText(format!("{} broke down", tile.tile_type.display_name())));
+            }
+
+            if !Self::is_damaged(tile) || existing.contains(&tile.tile_id) { continue };
+            let Some(mut tile_entity) = commands.get_entity(tile.entity) else { continue };
+            let size = Vec2::new(
+                tile.width() as f32 * TILE_SIZE,
+                tile.tile_type.collision_height().unwrap_or(TILE_SIZE),
+            );
+
+            tile_entity.with_children(|parent| {
+                parent.spawn((
+                    Sprite {
+                        image: asset_server.load("machines/cracks.png"),
+                        anchor: Anchor::BottomCenter,
+                        custom_size: Some(size),
+                        image_mode: SpriteImageMode::Tiled { tile_x: true, tile_y: true, stretch_value: 1.0 },
+                        color: Color::WHITE.with_alpha(if tile.is_broken() { 1.0 } else { 0.6 }),
+                        ..default()
+                    },
+                    Transform::from_xyz(0.0, 0.0, 0.5),
+                    DamagedOverlay { tile_id: tile.tile_id, broken: tile.is_broken() },
+                ))
+                .with_children(|parent| {
+                    if !tile.is_broken() { return }
+                    parent.spawn((
+                        Text2d::new("BROKEN"),
+                        TextFont {
+                            font: asset_server.load("fonts/ByteBounce.ttf"),
+                            font_size: 12.0,
+                            font_smoothing: FontSmoothing::None,
+                        },
+                        TextColor(hex!("#db1a1a")),
+                        Transform::from_xyz(0.0, size.y + BROKEN_TEXT_ELEVATION, 5.0),
+                    ));
+                });
+            });
+        }
+
+        *broken_tiles = planet.tiles.values()
+            .filter(|tile| tile.is_broken())
+            .map(|tile| tile.tile_id)
+            .collect();
+    }
+
+    fn is_damaged(tile: &Tile) -> bool {
+        tile.health < tile.max_health() * DAMAGED_THRESHOLD
+    }
+}
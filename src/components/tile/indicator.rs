@@ -0,0 +1,76 @@
+/* Imports */
+use bevy::{prelude::*, text::FontSmoothing, utils::HashMap};
+use crate::{components::planet::{Planet, PlayerPlanet}, utils::color::hex};
+
+/* Constants */
+const INDICATOR_ELEVATION: f32 = 56.0;
+const INDICATOR_BLINK_SPEED: f32 = 4.0;
+
+/// Shown above tiles that need energy to work
+/// but didn't get enough of it last tick. Broken
+/// tiles have their own indicator.
+#[derive(Component)]
+pub struct NoPowerIndicator {
+    tile_id: usize,
+}
+
+impl NoPowerIndicator {
+    /// Makes sure that only unpowered tiles have an
+    /// indicator. Tiles are respawned when upgraded, so
+    /// we also check that the indicator has the right parent.
+    fn update(
+        mut commands: Commands,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        indicator_q: Query<(Entity, &NoPowerIndicator, &Parent)>,
+        asset_server: Res<AssetServer>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        let mut existing: HashMap<usize, Entity> = HashMap::new();
+
+        for (entity, indicator, parent) in indicator_q.iter() {
+            let still_needed = planet.tiles.get(&indicator.tile_id)
+                .is_some_and(|tile| !tile.powergrid_status.powered && !tile.is_broken() && tile.entity == parent.get());
+
+            if still_needed {
+                existing.insert(indicator.tile_id, entity);
+            } else {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        for tile in planet.tiles.values() {
+            if tile.powergrid_status.powered || tile.is_broken() || existing.contains_key(&tile.tile_id) { continue };
+            let Some(mut tile_entity) = commands.get_entity(tile.entity) else { continue };
+
+            tile_entity.with_children(|parent| {
+                parent.spawn((
+                    Text2d::new("NO POWER"),
+                    TextFont {
+                        font: asset_server.load("fonts/ByteBounce.ttf"),
+                        font_size: 12.0,
+                        font_smoothing: FontSmoothing::None,
+                    },
+                    TextColor(hex!("#db1a1a")),
+                    Transform::from_xyz(0.0, INDICATOR_ELEVATION, 5.0),
+                    NoPowerIndicator { tile_id: tile.tile_id },
+                ));
+            });
+        }
+    }
+
+    /// Blinking so it's easier to spot
+    fn blink(time: Res<Time>, mut query: Query<&mut TextColor, With<NoPowerIndicator>>) -> () {
+        let alpha = ((time.elapsed_secs() * INDICATOR_BLINK_SPEED).sin() + 1.0) / 2.0;
+        for mut color in query.iter_mut() {
+            color.set_alpha(alpha);
+        }
+    }
+}
+
+pub struct NoPowerIndicatorPlugin;
+impl Plugin for NoPowerIndicatorPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, (NoPowerIndicator::update, NoPowerIndicator::blink));
+    }
+}
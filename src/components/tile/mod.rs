@@ -3,6 +3,8 @@ pub mod spawn;
 pub mod remove;
 pub mod upgrade;
 pub mod material;
+pub mod indicator;
+pub mod damage;
 pub use remove::RemoveTileCommand;
 
 mod tile;
@@ -29,6 +29,8 @@ impl Command for RemoveTileCommand {
                 }
             }
 
+            planet.cable_tiers.retain(|&(a, b), _| a != tile_id && b != tile_id);
+
             /* Remove tile */
             planet.tiles.remove(&tile_id);
         }
@@ -1,8 +1,8 @@
 /* Imports */
 use std::f32::consts::PI;
 use bevy::{audio::Volume, ecs::entity, prelude::*, render::texture, utils::hashbrown::HashSet};
-use crate::{camera::OuterCamera, components::{planet::{Planet, PlayerPlanet}, poi::{PointOfInterest, PointOfInterestHighlight, PointOfInterestType}}, systems::traits::GenericTile, ui::{info_text::SpawnInfoText, stats::{OpenStats, StatsPlugin}}, utils::{audio::{game_sounds, play_audio, PlayAudioEvent}, color::hex, logger}};
-use super::{material::TileMaterialOutline, types::{battery::Battery, debug::DebugTile, drill::Drill, loudspeaker::Loudspeaker, power_pole::PowerPole, solar_panel::SolarPanel, wind_turbine::WindTurbine}, Tile, TileType};
+use crate::{camera::OuterCamera, components::{planet::{Planet, PlayerPlanet}, player::player::Player, poi::{PointOfInterest, PointOfInterestHighlight, PointOfInterestType}}, systems::{game::{AppState, LifetimeStat, Profile, Research}, traits::GenericTile}, ui::{info_text::SpawnInfoText, stats::{OpenStats, StatsPlugin}}, utils::{audio::{game_sounds, play_audio, PlayAudioEvent}, color::hex, logger}};
+use super::{material::TileMaterialOutline, types::{battery::Battery, debug::DebugTile, drill::Drill, loudspeaker::Loudspeaker, power_pole::PowerPole, research_lab::ResearchLab, sensor::Sensor, solar_panel::SolarPanel, switch::{Breaker, Switch}, turret::EnergyTurret, wind_turbine::WindTurbine}, Tile, TileType};
 
 /* Constants */
 const TILE_PREVIEW_ELEVATION: f32 = 10.0;
@@ -57,6 +57,8 @@ impl TileSpawnPlugin {
         meshes: ResMut<Assets<Mesh>>,
         outline_material : ResMut<Assets<TileMaterialOutline>>,
         audio_events: EventWriter<PlayAudioEvent>,
+        player_q: Query<&Player>,
+        mut profile: ResMut<Profile>,
     ) {
         let Ok(planet) = planet_q.get_single_mut() else { return };
         let planet_entity = planet.planet_entity();
@@ -113,12 +115,20 @@ impl TileSpawnPlugin {
                     if Self::tile_fits(
                         &spawn_params.planet,
                         &spawn_data.tile.tile_type.width(),
-                        spawn_data.tile.tile_id
+                        spawn_data.tile.tile_id,
+                        None
                     ) == false {
                         logger::log::red("tile_plugin", "Tile does not fit");
                         return
                     };
 
+                    // Solid tiles can't be built on top of the player
+                    if Self::blocks_player(&spawn_params.planet, &spawn_data.tile.tile_type, spawn_data.tile.tile_id, &player_q) {
+                        logger::log::red("tile_plugin", "The player is in the way");
+                        commands.queue(SpawnInfoText("You're in the way".to_string()));
+                        return
+                    };
+
                     // If we have enough resources - spend them
                     if let Err(e) = spawn_params.planet.resources.try_spend(&spawn_data.tile.tile_type.cost()) {
                         logger::log::red("tile_plugin", e);
@@ -157,6 +167,7 @@ impl TileSpawnPlugin {
                         spawn_data.tile.interaction_range,
                         tile_entity.unwrap()
                     ));
+                    profile.add(LifetimeStat::TilesPlaced, 1.0);
                 }
 
                 // On click method
@@ -184,6 +195,7 @@ impl TileSpawnPlugin {
         planet_q: Query<(&Planet, &Transform), (With<Planet>, With<PlayerPlanet>, Without<TilePreview>)>,
         windows_q: Query<&Window>,
         camera_q: Query<(&Camera, &GlobalTransform), With<OuterCamera>>,
+        player_q: Query<&Player>,
     ) -> () {
         // If we have a preview active or not
         let Ok((tile_preview_entity, mut transform, TilePreview { tile_type, interaction_range })) = query.get_single_mut() else { return };
@@ -194,8 +206,6 @@ impl TileSpawnPlugin {
         let planet_rotation_z = planet_transform.rotation.to_euler(EulerRot::XYZ).2 - PI / 2.0;
         let planet_pos = planet_transform.translation.truncate();
 
-        commands.queue(SpawnInfoText("Press ESC to cancel".to_string()));
-        
         let Some(cursor_pos) = window
             .cursor_position()
             .and_then(|cursor| camera.viewport_to_world_2d(camera_transform, cursor).ok())
@@ -220,6 +230,18 @@ impl TileSpawnPlugin {
             });
         }
 
+        // Show what the tile would mine here (drills etc)
+        let preview_tile = Tile::new(index, tile_type.clone(), 0, *interaction_range, Entity::PLACEHOLDER);
+        let expected_yield = tile_type.expected_yield(&preview_tile, planet);
+        commands.queue(SpawnInfoText(if expected_yield.is_empty() {
+            "Press ESC to cancel".to_string()
+        } else {
+            let yields: Vec<String> = expected_yield.iter()
+                .map(|(resource, amount)| format!("{amount:.2} {resource:?}"))
+                .collect();
+            format!("{} per tick - Press ESC to cancel", yields.join(", "))
+        }));
+
         // Highlight some POI:s (drill highlights stones etc)
         if !tile_type.interacts_with().is_empty() {
             for poi_pos_index in planet.numbers_in_radius(index, *interaction_range) {
@@ -241,7 +263,8 @@ impl TileSpawnPlugin {
         };
 
         // If the tile does not fit, highlight the tile as red
-        if !Self::tile_fits(&planet, &tile_type.width(), index) {
+        if !Self::tile_fits(&planet, &tile_type.width(), index, None)
+            || Self::blocks_player(planet, tile_type, index, &player_q) {
             commands.get_entity(tile_preview_entity).map(|mut e| {
                 e.try_insert(PointOfInterestHighlight::red());
             });
@@ -257,6 +280,7 @@ impl TileSpawnPlugin {
         mut commands: Commands,
         mut event_writer: EventWriter<TileSpawnEvent>,
         preview_q: Query<Entity, With<TilePreview>>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
         kb: Res<ButtonInput<KeyCode>>,
     ) -> () {
         let mut tile: Option<TileType> = None;
@@ -265,15 +289,29 @@ impl TileSpawnPlugin {
         if kb.just_pressed(KeyCode::KeyQ) { tile = Some(TileType::PowerPole(PowerPole)); }
         if kb.just_pressed(KeyCode::KeyW) { tile = Some(TileType::DebugTile(DebugTile)); }
         if kb.just_pressed(KeyCode::KeyE) { tile = Some(TileType::SolarPanel(SolarPanel)); }
-        if kb.just_pressed(KeyCode::KeyR) { tile = Some(TileType::Drill(Drill)); }
+        if kb.just_pressed(KeyCode::KeyR) { tile = Some(TileType::Drill(Drill::default())); }
         if kb.just_pressed(KeyCode::KeyT) { tile = Some(TileType::Battery(Battery)); }
         if kb.just_pressed(KeyCode::KeyY) { tile = Some(TileType::WindTurbine(WindTurbine)); }
         if kb.just_pressed(KeyCode::KeyU) { tile = Some(TileType::Loudspeaker(Loudspeaker)); }
+        if kb.just_pressed(KeyCode::KeyI) { tile = Some(TileType::Switch(Switch)); }
+        if kb.just_pressed(KeyCode::KeyP) { tile = Some(TileType::Breaker(Breaker)); }
+        if kb.just_pressed(KeyCode::KeyK) { tile = Some(TileType::Sensor(Sensor::default())); }
+        if kb.just_pressed(KeyCode::KeyJ) { tile = Some(TileType::EnergyTurret(EnergyTurret)); }
+        if kb.just_pressed(KeyCode::KeyH) { tile = Some(TileType::ResearchLab(ResearchLab::default())); }
         if kb.just_pressed(KeyCode::Escape) {
             for entity in preview_q.iter() { commands.entity(entity).despawn_recursive(); }
         }
 
         if let Some(tile) = tile {
+            // Some tiles need to be researched first
+            let locked = planet_q.get_single().ok()
+                .filter(|planet| !planet.research.is_tile_unlocked(&tile))
+                .and_then(|_| Research::required_for_tile(&tile));
+            if let Some(technology) = locked {
+                commands.queue(SpawnInfoText(format!("Research {} first", technology.display_name())));
+                return;
+            }
+
             // Remove previews
             for entity in preview_q.iter() { commands.entity(entity).despawn_recursive(); }
 
@@ -320,16 +358,25 @@ impl TileSpawnPlugin {
         entities
     }
 
+    /// If a solid tile placed at `index` would end up on top of the player
+    fn blocks_player(planet: &Planet, tile_type: &TileType, index: usize, player_q: &Query<&Player>) -> bool {
+        if tile_type.collision_height().is_none() { return false }
+        let footprint = Tile::get_tile_spread(tile_type.width(), index, planet.tile_places());
+        player_q.iter().any(|player| footprint.contains(&planet.radians_to_index(player.radians)))
+    }
+
     /// Every tile_type has a width, which is the amount of tiles
     /// it occupies in the grid. This function returns an empty
     /// vector if the tile fits, otherwise it returns the entities
-    /// of the tiles that are in the way.
-    fn tile_fits(planet: &Planet, width: &usize, index: usize) -> bool {
+    /// of the tiles that are in the way. The tile at `ignore` isn't
+    /// counted, used when a tile grows from an upgrade.
+    pub(crate) fn tile_fits(planet: &Planet, width: &usize, index: usize, ignore: Option<usize>) -> bool {
         let mut occupied = HashSet::new();
 
         for i in planet.numbers_in_radius(index, 5) {
+            if ignore == Some(i) { continue }
             if let Some(tile) = &planet.tiles.get(&i) {
-                for position in Tile::get_tile_spread(tile.tile_type.width(), i, planet.tile_places()) {
+                for position in Tile::get_tile_spread(tile.width(), i, planet.tile_places()) {
                     occupied.insert(position);
                 }
             }
@@ -368,7 +415,7 @@ impl Plugin for TileSpawnPlugin {
             .add_systems(Update, (
                 Self::event_listener, Self::spawn_preview,
                 Self::update_preview
-            ))
+            ).run_if(in_state(AppState::InGame)))
             .add_event::<TileSpawnEvent>();
     }
 }
@@ -1,12 +1,18 @@
 /* Imports */
 use std::mem::discriminant;
 use bevy::{prelude::*, sprite::Material2dPlugin, utils::HashMap};
-use crate::{components::{planet::Planet, poi::PointOfInterestType}, systems::{game::PlanetResource, traits::{EnergyStorage, GenericTile, PowergridStatus}}};
-use super::{material::TileMaterialOutline, spawn::{TileSpawnEvent, TileSpawnEventParams, TileSpawnPlugin}, types::{battery::Battery, debug::DebugTile, drill::Drill, empty::EmptyTile, landed_rocket::LandedRocket, loudspeaker::{Loudspeaker, LoudspeakerPlugin}, power_pole::PowerPole, solar_panel::SolarPanel, wind_turbine::WindTurbine}};
+use crate::{components::{cable::slot_state::order, planet::Planet, poi::PointOfInterestType}, systems::{game::PlanetResource, traits::{EnergyStorage, GenericTile, PowerPriority, PowergridStatus, TileStat, TileUpgrade}}};
+use super::{damage::TileDamagePlugin, indicator::NoPowerIndicatorPlugin, material::TileMaterialOutline, spawn::{TileSpawnEvent, TileSpawnEventParams, TileSpawnPlugin}, types::{battery::Battery, debug::DebugTile, drill::Drill, empty::EmptyTile, landed_rocket::LandedRocket, loudspeaker::{Loudspeaker, LoudspeakerPlugin}, power_pole::PowerPole, research_lab::ResearchLab, sensor::Sensor, solar_panel::SolarPanel, switch::{Breaker, Switch, SwitchPlugin}, turret::{EnergyTurret, TurretPlugin}, wind_turbine::WindTurbine}};
 
 use crate::utils::audio::{PlayAudioEvent, game_sounds};
 /* Constants */
 pub const TILE_SIZE: f32 = 20.0;
+/// Energy amounts below this are considered empty
+const ENERGY_EPSILON: f32 = 0.0001;
+/// How much of the build cost a full repair costs
+const REPAIR_COST_FACTOR: f32 = 0.5;
+/// How well a tile with almost no health left works
+const MIN_EFFICIENCY: f32 = 0.5;
 
 /// A tile is something that can be placed on
 /// a planet. Can contain e.g solar panels,
@@ -30,6 +36,10 @@ pub struct Tile {
     /// Aka planet_position_index. The index of the tile
     /// in the planet's tile grid.
     pub tile_id: usize,
+
+    /// How much damage the tile can take before it's
+    /// destroyed, see [`GenericTile::max_health`]
+    pub health: f32,
 }
 
 /// Something that can be placed in a slot
@@ -45,6 +55,11 @@ pub enum TileType {
     WindTurbine(WindTurbine),
     LandedRocket(LandedRocket),
     Loudspeaker(Loudspeaker),
+    Switch(Switch),
+    Breaker(Breaker),
+    Sensor(Sensor),
+    EnergyTurret(EnergyTurret),
+    ResearchLab(ResearchLab),
 }
 
 // We only want to compare the type of Tile, the content
@@ -59,6 +74,7 @@ impl Tile {
     /// Creates a new tile
     pub fn new(tile_id: usize, tile_type: TileType, tile_level: usize, interaction_range: usize, entity: Entity) -> Self {
         Self {
+            health: tile_type.max_health(),
             tile_type,
             powergrid_status: PowergridStatus::default(),
             tile_id,
@@ -76,70 +92,268 @@ impl Tile {
     /// to have an external variable beacuse this function is 
     /// run for each individual tile, and we only want to run
     /// the `.on_energy_recieved` function once for each tile.
+    /// 
+    /// Recievers with a higher [`PowerPriority`] are filled up
+    /// first, whatever doesn't fit is passed on to the next one.
+    /// 
+    /// `cable_flow` is how much energy has passed through each
+    /// cable (keyed by `order(a, b)`) this tick, cables can't
+    /// carry more than their tier allows.
     pub fn distribute_energy_from(
         tile_id: usize,
         energy_output: f32,
         energy_to_add: &mut HashMap<usize, f32>,
+        energy_through: &mut HashMap<usize, f32>,
+        cable_flow: &mut HashMap<(usize, usize), f32>,
         planet: &Planet
     ) -> () {
         // HashMap<tile_id, will_recieve_energy>
         let mut visited: HashMap<usize, bool> = HashMap::new();
-        let mut recievers = 0;
+        // HashMap<tile_id, tile_id we came from>
+        let mut previous: HashMap<usize, usize> = HashMap::new();
 
         Self::search_tile(
-            &mut recievers,
             planet,
             tile_id,
-            &mut visited
+            &mut visited,
+            &mut previous,
         );
 
-        let energy_per_reciever = energy_output / recievers.max(1) as f32;
-        for (tile_id, will_recieve_energy) in visited {
-            if will_recieve_energy {
-                match energy_to_add.get_mut(&tile_id) {
-                    Some(e) => *e += energy_per_reciever,
-                    None => {
-                        energy_to_add.insert(tile_id, energy_per_reciever);
+        let mut energy_left = energy_output;
+        for priority in PowerPriority::ALL {
+            let recievers = visited.iter()
+                .filter(|(_, will_recieve_energy)| **will_recieve_energy)
+                .filter_map(|(tile_id, _)| planet.tiles.get(tile_id))
+                .filter(|tile| tile.powergrid_status.priority == priority && !tile.is_broken())
+                .map(|tile| tile.tile_id)
+                .collect::<Vec<usize>>();
+
+            let given = Self::fill_recievers(
+                tile_id,
+                &recievers,
+                &previous,
+                &mut energy_left,
+                energy_to_add,
+                cable_flow,
+                planet
+            );
+
+            // Keep track of how much energy passes through each
+            // tile on the way, breakers need to know this.
+            for (reciever, energy) in given {
+                let mut current = reciever;
+                while let Some(&prev) = previous.get(&current) {
+                    if prev == tile_id { break }
+                    *energy_through.entry(prev).or_insert(0.0) += energy;
+                    current = prev;
+                }
+            }
+
+            if energy_left <= ENERGY_EPSILON { break }
+        }
+    }
+
+    /// Shares `energy` evenly between `recievers` without
+    /// going over their capacity or what the cables on the
+    /// way can carry. Recievers that fill up leave their
+    /// share to the others. What's left of `energy` didn't
+    /// fit anywhere.
+    /// 
+    /// Returns Vec<(tile_id, energy given)>
+    fn fill_recievers(
+        source: usize,
+        recievers: &Vec<usize>,
+        previous: &HashMap<usize, usize>,
+        energy: &mut f32,
+        energy_to_add: &mut HashMap<usize, f32>,
+        cable_flow: &mut HashMap<(usize, usize), f32>,
+        planet: &Planet
+    ) -> Vec<(usize, f32)> {
+        let mut hungry = recievers.clone();
+        let mut given_total: HashMap<usize, f32> = HashMap::new();
+
+        while *energy > ENERGY_EPSILON && !hungry.is_empty() {
+            let share = *energy / hungry.len() as f32;
+            let mut still_hungry = Vec::new();
+
+            for tile_id in hungry {
+                let Some(tile) = planet.tiles.get(&tile_id) else { continue };
+                let pending = energy_to_add.get(&tile_id).cloned().unwrap_or(0.0);
+                let path = Self::cable_path(source, tile_id, previous);
+                let free_capacity = (tile.energy_capacity()
+                    - tile.powergrid_status.energy_stored - pending).max(0.0)
+                    .min(Self::path_capacity(&path, cable_flow, planet));
+
+                let given = share.min(free_capacity);
+                if given > 0.0 {
+                    *energy_to_add.entry(tile_id).or_insert(0.0) += given;
+                    *given_total.entry(tile_id).or_insert(0.0) += given;
+                    *energy -= given;
+                    for cable in path {
+                        *cable_flow.entry(cable).or_insert(0.0) += given;
                     }
                 }
+                if free_capacity > share {
+                    still_hungry.push(tile_id);
+                }
             }
+
+            hungry = still_hungry;
         }
+
+        given_total.into_iter().collect()
     }
 
-    
+    /// The cables (keyed by `order(a, b)`) energy passes
+    /// through on the way from `source` to `tile_id`
+    fn cable_path(source: usize, tile_id: usize, previous: &HashMap<usize, usize>) -> Vec<(usize, usize)> {
+        let mut path = Vec::new();
+        let mut current = tile_id;
+        while current != source {
+            let Some(&prev) = previous.get(&current) else { break };
+            path.push(order(prev, current));
+            current = prev;
+        }
+
+        path
+    }
 
+    /// How much more energy can pass through every cable in `path`
+    fn path_capacity(path: &Vec<(usize, usize)>, cable_flow: &HashMap<(usize, usize), f32>, planet: &Planet) -> f32 {
+        path.iter()
+            .map(|&(a, b)| planet.cable_tier(a, b).max_throughput()
+                - cable_flow.get(&(a, b)).cloned().unwrap_or(0.0))
+            .fold(f32::INFINITY, f32::min)
+            .max(0.0)
+    }
+
+    /// Walks the power grid from `tile_id`. Tiles that don't
+    /// conduct (like open switches) are visited, but energy
+    /// won't pass through them.
     fn search_tile(
-        recievers: &mut usize,
         planet: &Planet,
         tile_id: usize,
         visited: &mut HashMap<usize, bool>,
+        previous: &mut HashMap<usize, usize>,
     ) -> () {
         let Some(tile) = planet.tiles.get(&tile_id) else { return };
         visited.insert(tile_id, tile.can_recieve_energy());
-        if tile.can_recieve_energy() {
-            *recievers += 1;
-        }
+        if !tile.powergrid_status.conducting { return }
         
-        for tile_id in &tile.powergrid_status().connected_tiles {
-            if !visited.contains_key(tile_id) {
-                Self::search_tile(recievers, planet, *tile_id, visited);
+        for next_id in &tile.powergrid_status().connected_tiles {
+            if !visited.contains_key(next_id) {
+                previous.insert(*next_id, tile_id);
+                Self::search_tile(planet, *next_id, visited, previous);
             }
         }
     }
+    /// Energy produced per tick, including upgrades
+    /// and how worn down the tile is
     pub fn energy_output(&self) -> Option<f32> {
-        self.tile_type.energy_output(self)
+        self.tile_type.energy_output(self)?;
+        Some(self.stat(TileStat::EnergyOutput) * self.efficiency())
+    }
+    pub fn energy_consumption(&self) -> Option<f32> {
+        self.tile_type.energy_consumption(self)
     }
     pub fn can_recieve_energy(&self) -> bool {
         self.tile_type.can_recieve_energy()
     }
 
+    /// How much energy this tile can store, including upgrades
+    pub fn energy_capacity(&self) -> f32 {
+        self.stat(TileStat::EnergyCapacity)
+    }
+    /// How many tiles left and right this tile reaches, including upgrades
+    pub fn range(&self) -> usize {
+        self.stat(TileStat::InteractionRange).round().max(0.0) as usize
+    }
+    /// How many tile slots this tile takes up, including upgrades
+    pub fn width(&self) -> usize {
+        self.stat(TileStat::Width).round().max(1.0) as usize
+    }
+
+    /// The value of a stat at the current level
+    pub fn stat(&self, stat: TileStat) -> f32 {
+        self.stat_at_level(stat, self.tile_level)
+    }
+
+    /// The value of a stat with the modifiers of every upgrade up
+    /// to `level` applied. Levels past the upgrade table just
+    /// keep the value of the last upgrade.
+    pub fn stat_at_level(&self, stat: TileStat, level: usize) -> f32 {
+        self.tile_type.upgrades().iter()
+            .take(level)
+            .flat_map(|upgrade| upgrade.modifiers.iter())
+            .filter(|modifier| modifier.stat() == stat)
+            .fold(self.base_stat(stat), |value, modifier| modifier.apply(value))
+    }
+
+    /// The value of a stat before any upgrades
+    fn base_stat(&self, stat: TileStat) -> f32 {
+        match stat {
+            TileStat::EnergyOutput => self.tile_type.energy_output(self).unwrap_or(0.0),
+            TileStat::EnergyCapacity => self.tile_type.energy_capacity(self),
+            TileStat::InteractionRange => self.interaction_range as f32,
+            TileStat::Width => self.tile_type.width() as f32,
+            TileStat::DrillSpeed => 1.0,
+        }
+    }
+
+    /// The next upgrade of this tile, if there is one
+    pub fn next_upgrade(&self) -> Option<TileUpgrade> {
+        self.tile_type.upgrades().get(self.tile_level).cloned()
+    }
+
+    pub fn max_health(&self) -> f32 {
+        self.tile_type.max_health()
+    }
+    /// Broken tiles don't work until they're repaired
+    pub fn is_broken(&self) -> bool {
+        self.health <= 0.0
+    }
+    /// How well the tile works (0.0 - 1.0), damaged tiles
+    /// produce less and broken ones nothing at all
+    pub fn efficiency(&self) -> f32 {
+        if self.is_broken() { return 0.0 }
+        let health = (self.health / self.max_health()).clamp(0.0, 1.0);
+        MIN_EFFICIENCY + (1.0 - MIN_EFFICIENCY) * health
+    }
+    /// Wears the tile down, machines call this for
+    /// every tick they're working
+    pub fn wear(&mut self, amount: f32) -> () {
+        self.health = (self.health - amount).max(0.0);
+    }
+    /// What it costs to bring the tile back to full health,
+    /// up to half of the build cost for a tile that's almost
+    /// destroyed. Empty if the tile isn't damaged.
+    pub fn repair_cost(&self) -> Vec<(PlanetResource, usize)> {
+        let missing = 1.0 - (self.health / self.max_health()).clamp(0.0, 1.0);
+        if missing <= 0.0 { return Vec::new() }
+
+        self.tile_type.cost().into_iter()
+            .map(|(resource, amount)| (resource, (amount as f32 * missing * REPAIR_COST_FACTOR).ceil() as usize))
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+
+    /// How much of what this tile demanded it recieved
+    /// during the last tick (0.0 - 1.0). None for tiles
+    /// that don't consume energy.
+    pub fn satisfaction(&self) -> Option<f32> {
+        self.energy_consumption()?;
+        let status = &self.powergrid_status;
+        if status.energy_demanded <= ENERGY_EPSILON { return Some(1.0) }
+        Some((status.energy_received / status.energy_demanded).min(1.0))
+    }
+
     /// Adds energy to all tiles implementing `EnergyStorage`
     pub fn add_energy(planet: &mut Planet, tile_id: usize, energy: f32) -> () {
         // Add energy to the tile
         match planet.tiles.get_mut(&tile_id) {
             Some(e) => {
                 let stored = e.powergrid_status.energy_stored;
-                e.powergrid_status.energy_stored = (stored + energy).min(e.tile_type.energy_capacity(&e))
+                e.powergrid_status.energy_stored = (stored + energy).min(e.energy_capacity())
             },
             None => (),
         };
@@ -194,7 +408,11 @@ impl Plugin for TilePlugin {
                 // DrillPlugin,
                 Material2dPlugin::<TileMaterialOutline>::default(),
                 TileSpawnPlugin,
-                LoudspeakerPlugin
+                LoudspeakerPlugin,
+                NoPowerIndicatorPlugin,
+                SwitchPlugin,
+                TileDamagePlugin,
+                TurretPlugin,
             ));
     }
 }
@@ -202,6 +420,7 @@ impl Plugin for TilePlugin {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::components::{cable::tier::CableTier, poi::{copper::Copper, stone::Stone, PointOfInterest}, tile::types::sensor::SensorCondition};
 
     #[test]
     fn test_get_tile_spread() {
@@ -219,4 +438,129 @@ mod tests {
         let spread = Tile::get_tile_spread(8, 5, 20);
         assert_eq!(spread, vec![2, 3, 4, 5, 6, 7, 8, 9]);
     }
+
+    #[test]
+    fn distribute_energy_by_priority() {
+        let mut planet = Planet::default();
+        for (tile_id, tile_type) in [
+            (0, TileType::WindTurbine(WindTurbine)),
+            (1, TileType::Battery(Battery)),
+            (2, TileType::Drill(Drill::default())),
+        ] {
+            planet.tiles.insert(tile_id, Tile::new(tile_id, tile_type, 0, 0, Entity::PLACEHOLDER));
+        }
+        planet.powergrid_register_connection(0, 1);
+        planet.powergrid_register_connection(0, 2);
+        planet.tiles.get_mut(&1).unwrap().powergrid_status.priority = PowerPriority::Low;
+        planet.tiles.get_mut(&2).unwrap().powergrid_status.priority = PowerPriority::Critical;
+        planet.tiles.get_mut(&2).unwrap().powergrid_status.energy_stored = 48.0;
+
+        // The drill only has room for 2.0, the rest goes to the battery
+        let mut energy_to_add = HashMap::new();
+        Tile::distribute_energy_from(0, 5.0, &mut energy_to_add, &mut HashMap::new(), &mut HashMap::new(), &planet);
+        assert_eq!(energy_to_add.get(&2), Some(&2.0));
+        assert_eq!(energy_to_add.get(&1), Some(&3.0));
+    }
+
+    #[test]
+    fn distribute_energy_limited_by_cable() {
+        let mut planet = Planet::default();
+        planet.tiles.insert(0, Tile::new(0, TileType::WindTurbine(WindTurbine), 0, 0, Entity::PLACEHOLDER));
+        planet.tiles.insert(1, Tile::new(1, TileType::Battery(Battery), 0, 0, Entity::PLACEHOLDER));
+        planet.powergrid_register_cable(0, 1, CableTier::Basic);
+
+        let mut energy_to_add = HashMap::new();
+        Tile::distribute_energy_from(0, 30.0, &mut energy_to_add, &mut HashMap::new(), &mut HashMap::new(), &planet);
+        assert_eq!(energy_to_add.get(&1), Some(&CableTier::Basic.max_throughput()));
+    }
+
+    #[test]
+    fn sensor_switches_drills_on_full_batteries() {
+        let mut planet = Planet::default();
+        let sensor = Sensor { condition: SensorCondition::BatteryAbove(0.8), ..default() };
+        for (tile_id, tile_type) in [
+            (0, TileType::Battery(Battery)),
+            (1, TileType::Sensor(sensor)),
+            (2, TileType::Switch(Switch)),
+            (3, TileType::Drill(Drill::default())),
+        ] {
+            planet.tiles.insert(tile_id, Tile::new(tile_id, tile_type, 0, 0, Entity::PLACEHOLDER));
+        }
+        planet.powergrid_register_connection(0, 1);
+        planet.powergrid_register_connection(1, 2);
+        planet.powergrid_register_connection(2, 3);
+        let capacity = planet.tiles[&0].energy_capacity();
+
+        // Batteries at 50%, the switch opens and the drills are cut off
+        planet.tiles.get_mut(&0).unwrap().powergrid_status.energy_stored = capacity * 0.5;
+        Sensor::update(1, &mut planet);
+        assert!(!planet.powergrid_network(0).contains(&3));
+
+        // Batteries at 90%, the drills get power
+        planet.tiles.get_mut(&0).unwrap().powergrid_status.energy_stored = capacity * 0.9;
+        Sensor::update(1, &mut planet);
+        assert!(planet.powergrid_network(0).contains(&3));
+
+        // Opened by hand, the sensor leaves it until its state changes
+        Switch.on_toggle(2, &mut planet);
+        Sensor::update(1, &mut planet);
+        assert!(!planet.tiles[&2].powergrid_status.conducting);
+    }
+
+    #[test]
+    fn upgrade_modifiers() {
+        let tile = Tile::new(0, TileType::SolarPanel(SolarPanel), 2, 0, Entity::PLACEHOLDER);
+        assert_eq!(tile.energy_output(), Some(3.0));
+        assert_eq!(tile.stat_at_level(TileStat::EnergyOutput, 0), 1.0);
+
+        // Levels past the upgrade table keep the last value
+        let tile = Tile::new(0, TileType::SolarPanel(SolarPanel), 10, 0, Entity::PLACEHOLDER);
+        assert_eq!(tile.energy_output(), Some(5.0));
+        assert!(tile.next_upgrade().is_none());
+    }
+
+    #[test]
+    fn drill_yield() {
+        let drill_yield = |tile_level: usize, position_index: usize, poi_type: PointOfInterestType| {
+            let mut planet = Planet { radius: 1000.0, ..default() };
+            planet.points_of_interest.insert(position_index, vec![
+                PointOfInterest { position_index, poi_type, entity: Entity::PLACEHOLDER },
+            ]);
+            let drill = Tile::new(100, TileType::Drill(Drill::default()), tile_level, 30, Entity::PLACEHOLDER);
+            drill.tile_type.expected_yield(&drill, &planet)[0]
+        };
+
+        let centred = drill_yield(0, 100, PointOfInterestType::Stone(Stone));
+        let edge = drill_yield(0, 130, PointOfInterestType::Stone(Stone));
+        let copper = drill_yield(0, 100, PointOfInterestType::Copper(Copper));
+        let upgraded = drill_yield(1, 100, PointOfInterestType::Stone(Stone));
+        assert_eq!(centred.0, PlanetResource::Stone);
+        assert_eq!(copper.0, PlanetResource::Copper);
+        assert!(edge.1 < centred.1);
+        assert!(copper.1 < centred.1);
+        assert!(upgraded.1 > centred.1);
+    }
+
+    #[test]
+    fn worn_tiles_produce_less() {
+        let mut tile = Tile::new(0, TileType::WindTurbine(WindTurbine), 0, 0, Entity::PLACEHOLDER);
+        assert_eq!(tile.energy_output(), Some(5.0));
+
+        tile.health = tile.max_health() / 2.0;
+        assert_eq!(tile.energy_output(), Some(3.75));
+
+        tile.wear(tile.max_health());
+        assert!(tile.is_broken());
+        assert_eq!(tile.energy_output(), Some(0.0));
+    }
+
+    #[test]
+    fn repair_cost() {
+        let mut tile = Tile::new(0, TileType::Battery(Battery), 0, 0, Entity::PLACEHOLDER);
+        assert!(tile.repair_cost().is_empty());
+
+        // Almost destroyed costs half of the build cost, rounded up
+        tile.health = 1.0;
+        assert_eq!(tile.repair_cost(), vec![(PlanetResource::Wood, 1)]);
+    }
 }
@@ -1,6 +1,6 @@
 /* Imports */
 use bevy::{prelude::*, sprite::Anchor};
-use crate::{components::{cable::slot::CableSlot, planet::Planet, tile::{material::TileMaterialOutline, spawn::{TileSpawnEvent, TileSpawnEventParams}}}, systems::{game::PlanetResource, traits::GenericTile}};
+use crate::{components::{cable::slot::CableSlot, planet::Planet, tile::{material::TileMaterialOutline, spawn::{TileSpawnEvent, TileSpawnEventParams}}}, systems::{game::PlanetResource, traits::{GenericTile, StatModifier, TileStat, TileUpgrade}}};
 
 #[derive(Component, Clone, Debug)]
 pub struct Battery;
@@ -13,7 +13,7 @@ impl GenericTile for Battery {
         spawn_data: &TileSpawnEvent,
     ) -> Entity {
         let transform = spawn_params.planet.index_to_transform(
-            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.tile_type.width());
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width());
         if !spawn_data.is_preview {
             CableSlot::spawn(
                 commands, &spawn_params.asset_server, spawn_data.tile.tile_id, transform
@@ -59,8 +59,18 @@ impl GenericTile for Battery {
     }
 
     fn display_name(&self) -> String { "Battery".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(16.0) }
+    fn max_health(&self) -> f32 { 120.0 }
     fn width(&self) -> usize { 2 }
     fn can_recieve_energy(&self) -> bool { true }
+    fn upgrades(&self) -> Vec<TileUpgrade> {
+        vec![
+            TileUpgrade::new(vec![(PlanetResource::Stone, 6)])
+                .with(StatModifier::Multiply(TileStat::EnergyCapacity, 2.0)),
+            TileUpgrade::new(vec![(PlanetResource::Copper, 8)])
+                .with(StatModifier::Multiply(TileStat::EnergyCapacity, 2.0)),
+        ]
+    }
 
     fn cost(&self) -> Vec<(PlanetResource, usize)> {
         vec![
@@ -1,8 +1,15 @@
 /* Imports */
-use bevy::{audio::Volume, prelude::*, sprite::Anchor};
-use rand::Rng;
-use crate::{components::{cable::slot::CableSlot, planet::Planet, poi::{copper::Copper, stone::Stone, PointOfInterestType}, tile::spawn::{TileSpawnEvent, TileSpawnEventParams}}, systems::{game::PlanetResource, traits::GenericTile}, utils::{audio::{game_sounds, play_audio, PlayAudioEvent}, logger}};
-
+use bevy::{audio::Volume, prelude::*, sprite::Anchor, utils::HashMap};
+use crate::{components::{cable::slot::CableSlot, planet::Planet, poi::{copper::Copper, stone::Stone, PointOfInterestType}, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType}}, systems::{game::PlanetResource, traits::{GenericTile, StatModifier, TileStat, TileUpgrade}}, utils::audio::{game_sounds, play_audio, PlayAudioEvent}};
+
+/* Constants */
+/// Energy needed every tick to run
+const DRILL_CONSUMPTION: f32 = 5.0;
+/// How much of its speed a drill keeps for deposits
+/// at the very edge of its range
+const EDGE_FALLOFF: f32 = 0.25;
+/// Health lost every tick the drill is mining
+const DRILL_WEAR: f32 = 0.15;
 
 #[derive(Component)]
 struct AnimationIndices {
@@ -13,9 +20,25 @@ struct AnimationIndices {
 #[derive(Component, Deref, DerefMut)]
 struct AnimationTimer(Timer);
 
-/// Drills rocks sometimes I think...
-#[derive(Component, Clone, Debug)]
-pub struct Drill;
+/// Mines the deposits within its range. Closer deposits
+/// are mined faster, and copper is slower than stone.
+#[derive(Component, Clone, Debug, Default)]
+pub struct Drill {
+    /// Mined resources that don't add up to a whole unit yet
+    pub progress: HashMap<PlanetResource, f32>,
+}
+
+impl Drill {
+    /// What one deposit right next to the drill gives every tick
+    fn deposit_rate(poi_type: &PointOfInterestType) -> Option<(PlanetResource, f32)> {
+        match poi_type {
+            PointOfInterestType::Stone(_) => Some((PlanetResource::Stone, 0.2)),
+            PointOfInterestType::Copper(_) => Some((PlanetResource::Copper, 0.08)),
+            _ => None,
+        }
+    }
+}
+
 impl GenericTile for Drill {
     fn spawn(
         &self,
@@ -24,7 +47,7 @@ impl GenericTile for Drill {
         spawn_data: &TileSpawnEvent,
     ) -> Entity {
         let transform = spawn_params.planet.index_to_transform(
-            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.tile_type.width()
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width()
         );
 
         if !spawn_data.is_preview {
@@ -62,7 +85,7 @@ impl GenericTile for Drill {
             },
             AnimationTimer(Timer::from_seconds(0.1, TimerMode::Repeating)),
             animation_indices,
-            Drill,
+            self.clone(),
         )).id()
     }
 
@@ -74,71 +97,86 @@ impl GenericTile for Drill {
 
     fn interaction_range(&self) -> usize { 30 }
     fn width(&self) -> usize { 2 }
+    fn collision_height(&self) -> Option<f32> { Some(24.0) }
+    fn max_health(&self) -> f32 { 150.0 }
     fn display_name(&self) -> String { "Drill".to_string() }
     fn can_recieve_energy(&self) -> bool { true }
+    fn upgrades(&self) -> Vec<TileUpgrade> {
+        vec![
+            TileUpgrade::new(vec![(PlanetResource::Stone, 8)])
+                .with(StatModifier::Add(TileStat::DrillSpeed, 0.5)),
+            TileUpgrade::new(vec![(PlanetResource::Copper, 6)])
+                .with(StatModifier::Add(TileStat::InteractionRange, 10.0)),
+            TileUpgrade::new(vec![(PlanetResource::Stone, 10), (PlanetResource::Copper, 10)])
+                .with(StatModifier::Add(TileStat::DrillSpeed, 0.5))
+                .with(StatModifier::Add(TileStat::InteractionRange, 10.0)),
+        ]
+    }
+    fn energy_consumption(&self, _tile: &Tile) -> Option<f32> { Some(DRILL_CONSUMPTION) }
 
-    fn on_tick(&self, tile_id: usize, planet: &mut Planet, audio_events: &mut EventWriter<PlayAudioEvent>) {
-        let position_index = planet.tiles[&tile_id].tile_id;
-        let mut pois_in_range = Vec::new();
-        
-        let tile = planet.tiles.get(&tile_id).unwrap();
-
-        for poi_pos_index in planet.numbers_in_radius(position_index, tile.interaction_range) {
-            if let Some(local_pois) = planet.points_of_interest.get(&poi_pos_index) {
-                pois_in_range.extend(local_pois.iter().cloned());
+    fn expected_yield(&self, tile: &Tile, planet: &Planet) -> Vec<(PlanetResource, f32)> {
+        let range = tile.range();
+        let speed = tile.stat(TileStat::DrillSpeed) * tile.efficiency();
+        let mut yields: HashMap<PlanetResource, f32> = HashMap::new();
+
+        for poi_pos_index in planet.numbers_in_radius(tile.tile_id, range) {
+            let Some(local_pois) = planet.points_of_interest.get(&poi_pos_index) else { continue };
+            let distance = planet.index_distance(tile.tile_id, poi_pos_index) as f32;
+            let falloff = 1.0 - (1.0 - EDGE_FALLOFF) * distance / range.max(1) as f32;
+
+            for poi in local_pois {
+                let Some((resource, rate)) = Self::deposit_rate(&poi.poi_type) else { continue };
+                *yields.entry(resource).or_insert(0.0) += rate * falloff * speed;
             }
         }
 
+        PlanetResource::ALL.iter()
+            .filter_map(|resource| yields.get(resource).map(|amount| (*resource, *amount)))
+            .collect()
+    }
 
-    
-        if !pois_in_range.is_empty() {
-            let mut rng = rand::thread_rng();
-            let selected_poi = &pois_in_range[rng.gen_range(0..pois_in_range.len())];
-            
-            let (has_energy, width) = match planet.tiles.get_mut(&tile_id) {
-                Some(tile) if tile.powergrid_status.energy_stored >= 5.0 => {
-                    tile.powergrid_status.energy_stored -= 5.0;
-                    (true, tile.tile_type.width())
-                },
-                _ => (false, 0),
-            };
-    
-            if !has_energy { return; } // break if no energy
-    
-            match selected_poi.poi_type {
-                PointOfInterestType::Stone(_) => {
-                    planet.resources.add(PlanetResource::Stone, 1);
-                    let transform = planet.index_to_transform(position_index, 0.0, 1.0, width);
-                    play_audio(
-                        game_sounds::stone::DAMAGE,
-                        PlaybackSettings {
-                            mode: bevy::audio::PlaybackMode::Despawn,
-                            volume: Volume::new(0.4),
-                            spatial: true,
-                            ..Default::default()
-                        },
-                        Some(transform.translation),
-                        audio_events
-                    );
-                },
-                PointOfInterestType::Copper(_) => {
-                    planet.resources.add(PlanetResource::Copper, 1);
-                    let transform = planet.index_to_transform(position_index, 0.0, 1.0, width);
-                    play_audio(
-                        game_sounds::stone::DAMAGE,
-                        PlaybackSettings {
-                            mode: bevy::audio::PlaybackMode::Despawn,
-                            volume: Volume::new(0.4),
-                            spatial: true,
-                            ..Default::default()
-                        },
-                        Some(transform.translation),
-                        audio_events
-                    );
-                },
-                _ => {}
+    fn on_tick(&self, tile_id: usize, planet: &mut Planet, audio_events: &mut EventWriter<PlayAudioEvent>) {
+        let Some(tile) = planet.tiles.get(&tile_id) else { return };
+        let expected = self.expected_yield(tile, planet);
+        if expected.is_empty() { return }
+
+        let consumption = tile.energy_consumption().unwrap_or(0.0);
+        let Some(tile) = planet.tiles.get_mut(&tile_id) else { return };
+        if tile.powergrid_status.energy_stored < consumption { return } // no energy
+        tile.powergrid_status.energy_stored -= consumption;
+        tile.wear(DRILL_WEAR);
+        let width = tile.width();
+
+        // Only whole units are added to the planet,
+        // the rest is kept until the next tick
+        let mut mined = Vec::new();
+        if let TileType::Drill(drill) = &mut tile.tile_type {
+            for (resource, amount) in expected {
+                let progress = drill.progress.entry(resource).or_insert(0.0);
+                *progress += amount;
+                let whole = progress.floor();
+                *progress -= whole;
+                if whole >= 1.0 { mined.push((resource, whole as usize)); }
             }
         }
+
+        if mined.is_empty() { return }
+        for (resource, amount) in mined {
+            planet.resources.add(resource, amount);
+        }
+
+        let transform = planet.index_to_transform(tile_id, 0.0, 1.0, width);
+        play_audio(
+            game_sounds::stone::DAMAGE,
+            PlaybackSettings {
+                mode: bevy::audio::PlaybackMode::Despawn,
+                volume: Volume::new(0.4),
+                spatial: true,
+                ..Default::default()
+            },
+            Some(transform.translation),
+            audio_events
+        );
     }
 
     fn interacts_with(&self) -> Vec<PointOfInterestType> {
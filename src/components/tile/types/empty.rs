@@ -13,7 +13,7 @@ impl GenericTile for EmptyTile {
         spawn_data: &TileSpawnEvent,
     ) -> Entity {
         let transform = spawn_params.planet.index_to_transform(
-            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.tile_type.width());
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width());
         
         if !spawn_data.is_preview {
             CableSlot::spawn(
@@ -13,7 +13,7 @@ impl GenericTile for LandedRocket {
         spawn_data: &TileSpawnEvent,
     ) -> Entity {
         let transform = spawn_params.planet.index_to_transform(
-            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.tile_type.width());
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width());
         
         if !spawn_data.is_preview {
             CableSlot::spawn(
@@ -33,6 +33,7 @@ impl GenericTile for LandedRocket {
     }
 
     fn display_name(&self) -> String { "Landed rocket".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(60.0) }
     fn width(&self) -> usize { 3 }
     fn indestructible(&self) -> bool { true }
     fn cost(&self) -> Vec<(PlanetResource, usize)> { Vec::new() }
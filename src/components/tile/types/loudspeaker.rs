@@ -24,7 +24,7 @@ impl GenericTile for Loudspeaker {
         spawn_data: &TileSpawnEvent,
     ) -> Entity {
         let transform = spawn_params.planet.index_to_transform(
-            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.tile_type.width()
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width()
         );
 
         if !spawn_data.is_preview {
@@ -69,6 +69,7 @@ impl GenericTile for Loudspeaker {
         2
     }
     fn display_name(&self) -> String { "Loudspeaker".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(12.0) }
 }
 
 fn animate_sprite(
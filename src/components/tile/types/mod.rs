@@ -7,3 +7,7 @@ pub mod drill;
 pub mod battery;
 pub mod wind_turbine;
 pub mod loudspeaker;
+pub mod switch;
+pub mod sensor;
+pub mod turret;
+pub mod research_lab;
@@ -0,0 +1,112 @@
+/* Imports */
+use bevy::{prelude::*, sprite::Anchor};
+use crate::{
+    components::{cable::slot::CableSlot, planet::Planet, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType}},
+    systems::{game::PlanetResource, traits::GenericTile},
+    utils::audio::PlayAudioEvent,
+};
+
+/* Constants */
+const CABLE_SLOT_OFFSET: f32 = 22.0;
+/// Energy needed every tick to run
+const LAB_CONSUMPTION: f32 = 4.0;
+/// Research points made every tick at full health
+const LAB_POINTS: f32 = 0.5;
+
+/// Turns energy and resources into research points for
+/// whatever is being researched, see [`Research`]
+///
+/// [`Research`]: crate::systems::game::Research
+#[derive(Component, Clone, Debug, Default)]
+pub struct ResearchLab {
+    /// Research points that have been paid for but not made yet
+    pub paid: f32,
+}
+
+impl ResearchLab {
+    /// What one research point costs
+    fn point_cost() -> Vec<(PlanetResource, usize)> {
+        vec![
+            (PlanetResource::Wood, 1),
+            (PlanetResource::Stone, 1),
+        ]
+    }
+}
+
+impl GenericTile for ResearchLab {
+    fn spawn(
+        &self,
+        commands: &mut ChildBuilder,
+        spawn_params: &mut TileSpawnEventParams,
+        spawn_data: &TileSpawnEvent,
+    ) -> Entity {
+        let transform = spawn_params.planet.index_to_transform(
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width());
+        if !spawn_data.is_preview {
+            CableSlot::spawn(
+                commands, &spawn_params.asset_server, spawn_data.tile.tile_id, transform
+                    .with_translation(transform.translation
+                        + Planet::forward(&transform) * CABLE_SLOT_OFFSET)
+            );
+        }
+
+        commands.spawn((
+            transform,
+            self.clone(),
+            Visibility::Visible,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Sprite {
+                    image: spawn_params.asset_server.load("machines/research_lab.png"),
+                    anchor: Anchor::BottomCenter,
+                    ..default()
+                },
+                self.clone(),
+            ));
+        }).id()
+    }
+
+    fn display_name(&self) -> String { "Research lab".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(18.0) }
+    fn can_recieve_energy(&self) -> bool { true }
+    fn energy_consumption(&self, _tile: &Tile) -> Option<f32> { Some(LAB_CONSUMPTION) }
+
+    fn on_tick(&self, tile_id: usize, planet: &mut Planet, _audio_events: &mut EventWriter<PlayAudioEvent>) -> () {
+        if planet.research.current().is_none() && planet.research.available().is_empty() { return } // Nothing left
+
+        let Some(tile) = planet.tiles.get(&tile_id) else { return };
+        let consumption = tile.energy_consumption().unwrap_or(0.0);
+        if tile.powergrid_status.energy_stored < consumption { return } // no energy
+        let points = LAB_POINTS * tile.efficiency();
+
+        // Resources are paid for one whole point at a time
+        let mut paid = self.paid;
+        if paid < points {
+            if planet.resources.try_spend(&Self::point_cost()).is_err() { return }
+            paid += 1.0;
+        }
+
+        if let Some(tile) = planet.tiles.get_mut(&tile_id) {
+            tile.powergrid_status.energy_stored -= consumption;
+            if let TileType::ResearchLab(lab) = &mut tile.tile_type {
+                lab.paid = paid - points;
+            }
+        }
+        planet.research.add_points(points);
+    }
+
+    fn toggle_label(&self, _tile: &Tile) -> Option<String> {
+        Some("Change research".to_string())
+    }
+    fn on_toggle(&self, _tile_id: usize, planet: &mut Planet) -> () {
+        planet.research.select_next();
+    }
+
+    fn cost(&self) -> Vec<(PlanetResource, usize)> {
+        vec![
+            (PlanetResource::Wood, 6),
+            (PlanetResource::Stone, 4),
+        ]
+    }
+}
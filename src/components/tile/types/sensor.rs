@@ -0,0 +1,159 @@
+/* Imports */
+use bevy::prelude::*;
+use crate::{components::{planet::Planet, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType}}, systems::{game::PlanetResource, traits::GenericTile}, utils::audio::PlayAudioEvent};
+use super::switch::spawn_control_tile;
+
+/* Constants */
+const SENSOR_COLOR: &str = "#3a6fd1";
+
+/// What a sensor is measuring. Cycled through with
+/// the toggle button in the stats menu.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SensorCondition {
+    /// Batteries in the same network are above a fraction of their capacity
+    BatteryAbove(f32),
+    /// Batteries in the same network are below a fraction of their capacity
+    BatteryBelow(f32),
+    Daylight,
+    Night,
+    ResourceAbove(PlanetResource, usize),
+    ResourceBelow(PlanetResource, usize),
+}
+
+impl SensorCondition {
+    /// Conditions the player can choose between
+    pub const PRESETS: [SensorCondition; 9] = [
+        Self::BatteryAbove(0.8),
+        Self::BatteryAbove(0.5),
+        Self::BatteryBelow(0.2),
+        Self::Daylight,
+        Self::Night,
+        Self::ResourceBelow(PlanetResource::Wood, 200),
+        Self::ResourceBelow(PlanetResource::Stone, 200),
+        Self::ResourceBelow(PlanetResource::Copper, 200),
+        Self::ResourceAbove(PlanetResource::Copper, 500),
+    ];
+
+    /// Next preset, wraps around
+    pub fn next(&self) -> Self {
+        let index = Self::PRESETS.iter().position(|e| e == self).unwrap_or(0);
+        Self::PRESETS[(index + 1) % Self::PRESETS.len()]
+    }
+
+    pub fn display_name(&self) -> String {
+        match self {
+            Self::BatteryAbove(f) => format!("Battery > {}%", (f * 100.0) as usize),
+            Self::BatteryBelow(f) => format!("Battery < {}%", (f * 100.0) as usize),
+            Self::Daylight => "Daylight".to_string(),
+            Self::Night => "Night".to_string(),
+            Self::ResourceAbove(resource, amount) => format!("{resource:?} > {amount}"),
+            Self::ResourceBelow(resource, amount) => format!("{resource:?} < {amount}"),
+        }
+    }
+
+    /// If the condition is met for a sensor placed at `tile_id`
+    fn evaluate(&self, tile_id: usize, planet: &Planet) -> bool {
+        match *self {
+            Self::BatteryAbove(fraction) => Self::battery_level(tile_id, planet)
+                .is_some_and(|level| level > fraction),
+            Self::BatteryBelow(fraction) => Self::battery_level(tile_id, planet)
+                .is_some_and(|level| level < fraction),
+            Self::Daylight => planet.is_daylight(tile_id),
+            Self::Night => !planet.is_daylight(tile_id),
+            Self::ResourceAbove(resource, amount) => planet.resources.get(resource) > amount,
+            Self::ResourceBelow(resource, amount) => planet.resources.get(resource) < amount,
+        }
+    }
+
+    /// How full the batteries connected to `tile_id` are (0.0 - 1.0).
+    /// None if there are no batteries in the network.
+    fn battery_level(tile_id: usize, planet: &Planet) -> Option<f32> {
+        let (stored, capacity) = planet.powergrid_network(tile_id).iter()
+            .filter_map(|id| planet.tiles.get(id))
+            .filter(|tile| matches!(tile.tile_type, TileType::Battery(_)))
+            .fold((0.0, 0.0), |(stored, capacity), tile| (
+                stored + tile.powergrid_status.energy_stored,
+                capacity + tile.energy_capacity(),
+            ));
+
+        if capacity > 0.0 { Some(stored / capacity) } else { None }
+    }
+}
+
+/// Measures something every tick. When the condition starts
+/// being met it closes the switches it's linked to by cable,
+/// and opens them when it stops. In between the switches can
+/// still be toggled by hand.
+#[derive(Component, Clone, Debug)]
+pub struct Sensor {
+    pub condition: SensorCondition,
+
+    /// If the condition was met during the last
+    /// tick, `None` before the first one
+    pub active: Option<bool>,
+}
+
+impl Default for Sensor {
+    fn default() -> Self {
+        Self {
+            condition: SensorCondition::PRESETS[0],
+            active: None,
+        }
+    }
+}
+
+impl Sensor {
+    /// Evaluates the condition of the sensor at `tile_id`, and sets
+    /// the switches it's linked to if it changed since the last tick
+    pub fn update(tile_id: usize, planet: &mut Planet) -> () {
+        let Some(Tile { tile_type: TileType::Sensor(sensor), powergrid_status, .. }) = planet.tiles.get(&tile_id) else { return };
+        let active = sensor.condition.evaluate(tile_id, planet);
+        if sensor.active == Some(active) { return }
+        let linked = powergrid_status.connected_tiles.clone();
+
+        if let Some(Tile { tile_type: TileType::Sensor(sensor), .. }) = planet.tiles.get_mut(&tile_id) {
+            sensor.active = Some(active);
+        }
+        for linked_id in linked {
+            if let Some(tile) = planet.tiles.get_mut(&linked_id) {
+                if let TileType::Switch(_) = tile.tile_type {
+                    tile.powergrid_status.conducting = active;
+                }
+            }
+        }
+    }
+}
+
+impl GenericTile for Sensor {
+    fn spawn(
+        &self,
+        commands: &mut ChildBuilder,
+        spawn_params: &mut TileSpawnEventParams,
+        spawn_data: &TileSpawnEvent,
+    ) -> Entity {
+        spawn_control_tile(commands, spawn_params, spawn_data, self.clone(), SENSOR_COLOR)
+    }
+
+    fn display_name(&self) -> String { "Sensor".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(8.0) }
+
+    fn on_tick(&self, tile_id: usize, planet: &mut Planet, _audio_events: &mut EventWriter<PlayAudioEvent>) -> () {
+        Sensor::update(tile_id, planet);
+    }
+
+    fn toggle_label(&self, _tile: &Tile) -> Option<String> {
+        Some(format!("Mode: {}", self.condition.display_name()))
+    }
+    fn on_toggle(&self, tile_id: usize, planet: &mut Planet) -> () {
+        if let Some(Tile { tile_type: TileType::Sensor(sensor), .. }) = planet.tiles.get_mut(&tile_id) {
+            sensor.condition = sensor.condition.next();
+        }
+    }
+
+    fn cost(&self) -> Vec<(PlanetResource, usize)> {
+        vec![
+            (PlanetResource::Stone, 2),
+            (PlanetResource::Copper, 3),
+        ]
+    }
+}
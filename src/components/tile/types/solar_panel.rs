@@ -1,6 +1,6 @@
 /* Imports */
 use bevy::{prelude::*, sprite::Anchor};
-use crate::{components::{cable::slot::CableSlot, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile}}, systems::{game::PlanetResource, traits::GenericTile}};
+use crate::{components::{cable::slot::CableSlot, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile}}, systems::{game::PlanetResource, traits::{GenericTile, StatModifier, TileStat, TileUpgrade}}};
 
 /// A solar panel is a tile that generates energy
 /// if sun is shining on it.
@@ -14,7 +14,7 @@ impl GenericTile for SolarPanel {
         spawn_data: &TileSpawnEvent,
     ) -> Entity {
         let transform = spawn_params.planet.index_to_transform(
-            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.tile_type.width());
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width());
         
         if !spawn_data.is_preview {
             CableSlot::spawn(
@@ -35,23 +35,22 @@ impl GenericTile for SolarPanel {
     }
 
     fn display_name(&self) -> String { "Solar panel".to_string() }
-    fn energy_output(&self, tile: &Tile) -> Option<f32> {
-        Some(match tile.tile_level {
-            0 => 1.0,
-            1 => 2.0,
-            2 => 3.0,
-            3 => 4.0,
-            4 => 5.0,
-            _ => unimplemented!()
-        })
-    }
-    fn upgrades(&self) -> Vec<Vec<(PlanetResource,usize)> > {
+    fn collision_height(&self) -> Option<f32> { Some(10.0) }
+    fn max_health(&self) -> f32 { 60.0 }
+    fn energy_output(&self, _tile: &Tile) -> Option<f32> { Some(1.0) }
+
+    /// One texture per level, so there can't be more
+    /// upgrades than textures in `machines/solar_panel`
+    fn upgrades(&self) -> Vec<TileUpgrade> {
         vec![
-            vec![(PlanetResource::Wood, 4)],
-            vec![(PlanetResource::Stone, 4)],
-            vec![(PlanetResource::Stone, 4)],
-            vec![(PlanetResource::Stone, 4)],
-            vec![(PlanetResource::Stone, 4)],
+            TileUpgrade::new(vec![(PlanetResource::Wood, 4)])
+                .with(StatModifier::Add(TileStat::EnergyOutput, 1.0)),
+            TileUpgrade::new(vec![(PlanetResource::Stone, 4)])
+                .with(StatModifier::Add(TileStat::EnergyOutput, 1.0)),
+            TileUpgrade::new(vec![(PlanetResource::Stone, 4)])
+                .with(StatModifier::Add(TileStat::EnergyOutput, 1.0)),
+            TileUpgrade::new(vec![(PlanetResource::Stone, 4)])
+                .with(StatModifier::Add(TileStat::EnergyOutput, 1.0)),
         ]
     }
 
@@ -0,0 +1,160 @@
+/* Imports */
+use bevy::{prelude::*, sprite::Anchor};
+use crate::{components::{cable::slot::CableSlot, planet::{Planet, PlayerPlanet}, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType}}, systems::{game::PlanetResource, traits::GenericTile}, utils::color::hex};
+
+/* Constants */
+const CABLE_SLOT_OFFSET: f32 = 24.0;
+const LAMP_ON_COLOR: &str = "#3ad13a";
+const LAMP_OFF_COLOR: &str = "#db1a1a";
+const SWITCH_COLOR: &str = "#8a8f98";
+const BREAKER_COLOR: &str = "#5c5f66";
+/// How much energy can pass through a breaker every tick
+const BREAKER_MAX_THROUGHPUT: f32 = 10.0;
+
+/// Small lamp on switches, breakers and sensors showing
+/// if they're on (green) or off (red).
+#[derive(Component)]
+pub struct StateLamp {
+    pub tile_id: usize,
+}
+
+/// Cuts the power grid in two when opened. Can be
+/// toggled by hand or by sensors linked to it by cable.
+#[derive(Component, Clone, Debug)]
+pub struct Switch;
+impl GenericTile for Switch {
+    fn spawn(
+        &self,
+        commands: &mut ChildBuilder,
+        spawn_params: &mut TileSpawnEventParams,
+        spawn_data: &TileSpawnEvent,
+    ) -> Entity {
+        spawn_control_tile(commands, spawn_params, spawn_data, self.clone(), SWITCH_COLOR)
+    }
+
+    fn display_name(&self) -> String { "Switch".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(8.0) }
+    fn toggle_label(&self, tile: &Tile) -> Option<String> {
+        Some(if tile.powergrid_status.conducting { "Open" } else { "Close" }.to_string())
+    }
+    fn on_toggle(&self, tile_id: usize, planet: &mut Planet) -> () {
+        if let Some(tile) = planet.tiles.get_mut(&tile_id) {
+            tile.powergrid_status.conducting = !tile.powergrid_status.conducting;
+        }
+    }
+
+    fn cost(&self) -> Vec<(PlanetResource, usize)> {
+        vec![
+            (PlanetResource::Wood, 2),
+            (PlanetResource::Copper, 2),
+        ]
+    }
+}
+
+/// Opens by itself if too much energy passes through
+/// it during one tick. Needs to be reset by hand.
+#[derive(Component, Clone, Debug)]
+pub struct Breaker;
+impl GenericTile for Breaker {
+    fn spawn(
+        &self,
+        commands: &mut ChildBuilder,
+        spawn_params: &mut TileSpawnEventParams,
+        spawn_data: &TileSpawnEvent,
+    ) -> Entity {
+        spawn_control_tile(commands, spawn_params, spawn_data, self.clone(), BREAKER_COLOR)
+    }
+
+    fn display_name(&self) -> String { "Breaker".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(8.0) }
+    fn max_throughput(&self) -> Option<f32> { Some(BREAKER_MAX_THROUGHPUT) }
+    fn toggle_label(&self, tile: &Tile) -> Option<String> {
+        Some(if tile.powergrid_status.conducting { "Trip" } else { "Reset" }.to_string())
+    }
+    fn on_toggle(&self, tile_id: usize, planet: &mut Planet) -> () {
+        if let Some(tile) = planet.tiles.get_mut(&tile_id) {
+            tile.powergrid_status.conducting = !tile.powergrid_status.conducting;
+        }
+    }
+
+    fn cost(&self) -> Vec<(PlanetResource, usize)> {
+        vec![
+            (PlanetResource::Copper, 4),
+        ]
+    }
+}
+
+/// Switches, breakers and sensors all look the same except
+/// for their color, they're small boxes with a lamp on top.
+pub fn spawn_control_tile(
+    commands: &mut ChildBuilder,
+    spawn_params: &mut TileSpawnEventParams,
+    spawn_data: &TileSpawnEvent,
+    component: impl Component,
+    color: &str,
+) -> Entity {
+    let tile_id = spawn_data.tile.tile_id;
+    let transform = spawn_params.planet.index_to_transform(
+        tile_id, 0.0, 1.0, spawn_data.tile.width());
+
+    if !spawn_data.is_preview {
+        CableSlot::spawn(
+            commands, &spawn_params.asset_server, tile_id, transform
+                .with_translation(transform.translation
+                    + Planet::forward(&transform) * CABLE_SLOT_OFFSET)
+        );
+    }
+
+    commands.spawn((
+        transform,
+        Visibility::Visible,
+        component,
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            Sprite {
+                image: spawn_params.asset_server.load("machines/debug.png"),
+                color: hex!(color),
+                anchor: Anchor::BottomCenter,
+                custom_size: Some(Vec2::new(14.0, 20.0)),
+                ..default()
+            },
+        ));
+        parent.spawn((
+            Sprite {
+                color: hex!(LAMP_OFF_COLOR),
+                custom_size: Some(Vec2::splat(4.0)),
+                ..default()
+            },
+            Transform::from_xyz(0.0, 15.0, 0.1),
+            StateLamp { tile_id },
+        ));
+    })
+    .id()
+}
+
+impl StateLamp {
+    fn update(
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        mut lamp_q: Query<(&StateLamp, &mut Sprite)>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        for (lamp, mut sprite) in lamp_q.iter_mut() {
+            let Some(tile) = planet.tiles.get(&lamp.tile_id) else { continue };
+            let on = match &tile.tile_type {
+                TileType::Sensor(sensor) => sensor.active == Some(true),
+                _ => tile.powergrid_status.conducting,
+            };
+
+            sprite.color = if on { hex!(LAMP_ON_COLOR) } else { hex!(LAMP_OFF_COLOR) };
+        }
+    }
+}
+
+pub struct SwitchPlugin;
+impl Plugin for SwitchPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Update, StateLamp::update);
+    }
+}
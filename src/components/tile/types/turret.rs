@@ -0,0 +1,168 @@
+/* Imports */
+use bevy::{prelude::*, sprite::Anchor};
+use crate::{
+    components::{cable::slot::CableSlot, creature::Creature, planet::{Planet, PlayerPlanet}, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType}},
+    functional::damageable::{DamageEvent, DamageType, Flashing},
+    systems::{game::{GameTickSet, PlanetResource}, traits::{GenericTile, StatModifier, TileStat, TileUpgrade}},
+    utils::color::hex,
+};
+
+/* Constants */
+const CABLE_SLOT_OFFSET: f32 = 14.0;
+/// How many tiles left and right the turret shoots
+const TURRET_RANGE: usize = 12;
+/// Energy used for every shot, at most one shot every tick
+const SHOT_ENERGY: f32 = 5.0;
+const SHOT_DAMAGE: f32 = 8.0;
+/// Where the beam comes out, above the ground
+const BARREL_HEIGHT: f32 = 17.0;
+/// Where the beam hits creatures, above the ground
+const TARGET_HEIGHT: f32 = 5.0;
+const BEAM_COLOR: &str = "#7fe8ff";
+const BEAM_WIDTH: f32 = 2.0;
+/// Seconds the beam stays visible
+const BEAM_DURATION: f32 = 0.15;
+
+/// Shoots creatures within range with energy from the grid
+#[derive(Component, Clone, Debug)]
+pub struct EnergyTurret;
+
+impl GenericTile for EnergyTurret {
+    fn spawn(
+        &self,
+        commands: &mut ChildBuilder,
+        spawn_params: &mut TileSpawnEventParams,
+        spawn_data: &TileSpawnEvent,
+    ) -> Entity {
+        let transform = spawn_params.planet.index_to_transform(
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width());
+        if !spawn_data.is_preview {
+            CableSlot::spawn(
+                commands, &spawn_params.asset_server, spawn_data.tile.tile_id, transform
+                    .with_translation(transform.translation
+                        + Planet::forward(&transform) * CABLE_SLOT_OFFSET)
+            );
+        }
+
+        commands.spawn((
+            transform,
+            self.clone(),
+            Visibility::Visible,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Sprite {
+                    image: spawn_params.asset_server.load("machines/turret.png"),
+                    anchor: Anchor::BottomCenter,
+                    ..default()
+                },
+                self.clone(),
+            ));
+        }).id()
+    }
+
+    fn display_name(&self) -> String { "Energy turret".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(20.0) }
+    fn interaction_range(&self) -> usize { TURRET_RANGE }
+    fn can_recieve_energy(&self) -> bool { true }
+    fn energy_consumption(&self, _tile: &Tile) -> Option<f32> { Some(SHOT_ENERGY) }
+    fn energy_capacity(&self, _tile: &Tile) -> f32 { SHOT_ENERGY * 6.0 }
+    fn upgrades(&self) -> Vec<TileUpgrade> {
+        vec![
+            TileUpgrade::new(vec![(PlanetResource::Copper, 6)])
+                .with(StatModifier::Add(TileStat::InteractionRange, 4.0)),
+            TileUpgrade::new(vec![(PlanetResource::Stone, 8), (PlanetResource::Copper, 8)])
+                .with(StatModifier::Multiply(TileStat::EnergyCapacity, 2.0)),
+        ]
+    }
+
+    fn cost(&self) -> Vec<(PlanetResource, usize)> {
+        vec![
+            (PlanetResource::Stone, 4),
+            (PlanetResource::Copper, 4),
+        ]
+    }
+}
+
+impl EnergyTurret {
+    /// Every turret with enough energy stored shoots
+    /// the closest creature within its range
+    fn shoot(
+        mut commands: Commands,
+        mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+        creature_q: Query<(Entity, &Creature)>,
+        mut damage_events: EventWriter<DamageEvent>,
+    ) -> () {
+        let Ok(mut planet) = planet_q.get_single_mut() else { return };
+        let turrets: Vec<usize> = planet.tiles.values()
+            .filter(|tile| matches!(tile.tile_type, TileType::EnergyTurret(_)))
+            .filter(|tile| !tile.is_broken() && tile.powergrid_status.energy_stored >= SHOT_ENERGY)
+            .map(|tile| tile.tile_id)
+            .collect();
+
+        for tile_id in turrets {
+            let Some(tile) = planet.tiles.get(&tile_id) else { continue };
+            let (range, width) = (tile.range(), tile.width());
+            let target = creature_q.iter()
+                .map(|(entity, creature)| (entity, creature.radians,
+                    planet.index_distance(tile_id, planet.radians_to_index(creature.radians))))
+                .filter(|(_, _, distance)| *distance <= range)
+                .min_by_key(|(_, _, distance)| *distance);
+            let Some((target_entity, radians, _)) = target else { continue };
+
+            let Some(tile) = planet.tiles.get_mut(&tile_id) else { continue };
+            tile.powergrid_status.energy_stored -= SHOT_ENERGY;
+            let damage = SHOT_DAMAGE * tile.efficiency();
+            damage_events.send(DamageEvent { target_entity, damage, damage_type: DamageType::Energy });
+            commands.entity(target_entity).insert_if_new(Flashing::new());
+
+            let turret = planet.index_to_transform(tile_id, 0.0, 1.0, width);
+            let from = (turret.translation + Planet::forward(&turret) * BARREL_HEIGHT).truncate();
+            let to = planet.radians_to_transform(radians, TARGET_HEIGHT, 1.0).translation.truncate();
+            TurretBeam::spawn(&mut commands, from, to);
+        }
+    }
+}
+
+/// The beam of a shot, fades out quickly
+#[derive(Component)]
+pub struct TurretBeam(Timer);
+
+impl TurretBeam {
+    fn spawn(commands: &mut Commands, from: Vec2, to: Vec2) -> () {
+        let direction = to - from;
+        commands.spawn((
+            TurretBeam(Timer::from_seconds(BEAM_DURATION, TimerMode::Once)),
+            Sprite {
+                color: hex!(BEAM_COLOR),
+                custom_size: Some(Vec2::new(direction.length(), BEAM_WIDTH)),
+                ..default()
+            },
+            Transform::from_translation(((from + to) / 2.0).extend(9.5))
+                .with_rotation(Quat::from_rotation_z(direction.y.atan2(direction.x))),
+        ));
+    }
+
+    fn update(
+        mut commands: Commands,
+        time: Res<Time>,
+        mut beam_q: Query<(Entity, &mut TurretBeam, &mut Sprite)>,
+    ) -> () {
+        for (entity, mut beam, mut sprite) in beam_q.iter_mut() {
+            beam.0.tick(time.delta());
+            sprite.color.set_alpha(1.0 - beam.0.fraction());
+            if beam.0.finished() {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+}
+
+pub struct TurretPlugin;
+impl Plugin for TurretPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(FixedUpdate, EnergyTurret::shoot.in_set(GameTickSet))
+            .add_systems(Update, TurretBeam::update);
+    }
+}
@@ -1,10 +1,12 @@
 /* Imports */
 use std::f32::consts::TAU;
 use bevy::{prelude::*, sprite::Anchor};
-use crate::{components::{cable::slot::CableSlot, foliage::animation::Rotate, planet::Planet, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType}}, systems::{game::PlanetResource, traits::GenericTile}};
+use crate::{components::{cable::slot::CableSlot, foliage::animation::Rotate, planet::Planet, tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType}}, systems::{game::PlanetResource, traits::{GenericTile, StatModifier, TileStat, TileUpgrade}}, utils::audio::PlayAudioEvent};
 
 /* Constants */
 const CABLE_SLOT_OFFSET: f32 = 28.0;
+/// Health lost every tick, the rotors never stop
+const WIND_TURBINE_WEAR: f32 = 0.1;
 
 #[derive(Component, Clone, Debug)]
 pub struct WindTurbine;
@@ -16,7 +18,7 @@ impl GenericTile for WindTurbine {
         spawn_data: &TileSpawnEvent,
     ) -> Entity {
         let transform = spawn_params.planet.index_to_transform(
-            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.tile_type.width());
+            spawn_data.tile.tile_id, 0.0, 1.0, spawn_data.tile.width());
 
         if !spawn_data.is_preview {
             CableSlot::spawn(
@@ -71,7 +73,21 @@ impl GenericTile for WindTurbine {
     }
 
     fn energy_output(&self, _tile: &Tile) -> Option<f32> { Some(5.0) }
+    fn on_tick(&self, tile_id: usize, planet: &mut Planet, _audio_events: &mut EventWriter<PlayAudioEvent>) -> () {
+        if let Some(tile) = planet.tiles.get_mut(&tile_id) {
+            tile.wear(WIND_TURBINE_WEAR);
+        }
+    }
     fn display_name(&self) -> String { "Wind turbine".to_string() }
+    fn collision_height(&self) -> Option<f32> { Some(40.0) }
+    fn upgrades(&self) -> Vec<TileUpgrade> {
+        vec![
+            TileUpgrade::new(vec![(PlanetResource::Stone, 6)])
+                .with(StatModifier::Add(TileStat::EnergyOutput, 2.0)),
+            TileUpgrade::new(vec![(PlanetResource::Copper, 6)])
+                .with(StatModifier::Add(TileStat::EnergyOutput, 3.0)),
+        ]
+    }
 
     // So wind turbine rotors don't overlap
     fn keep_distance_from(&self) -> Vec<(usize,crate::components::tile::TileType)> {
@@ -1,7 +1,7 @@
 /* Imports */
 use bevy::{prelude::*, state::state::setup_state_transitions_in_world};
-use crate::{components::{cable::slot::{CableSlot, RemoveAllCableSlotHighlightsCommand, RemoveCableSlotCommand}, planet::{Planet, PlayerPlanet}}, systems::{game::GameState, traits::GenericTile}, utils::logger};
-use super::spawn::TileSpawnEvent;
+use crate::{components::{cable::slot::{CableSlot, RemoveAllCableSlotHighlightsCommand, RemoveCableSlotCommand}, planet::{Planet, PlayerPlanet}}, systems::{game::{GameState, Research}, traits::GenericTile}, ui::info_text::SpawnInfoText, utils::logger};
+use super::spawn::{TileSpawnEvent, TileSpawnPlugin};
 
 /* Constants */
 
@@ -17,17 +17,32 @@ impl Command for UpgradeTileCommand {
         let mut query_state = world.query_filtered::<&mut Planet, With<PlayerPlanet>>();
         if let Ok(mut planet) = query_state.get_single_mut(world) {
             let Some(tile) = planet.tiles.get(&tile_id).cloned() else { return };
-            let tile_upgrades = tile.tile_type.upgrades();
-            let upgrade_costs = match tile_upgrades.get(tile.tile_level) {
-                Some(e) => e,
-                None => {
-                    logger::log::bright_red("tile_upgrade", "No more upgrades available for this tile");
-                    return
-                },
+            let Some(upgrade) = tile.next_upgrade() else {
+                logger::log::bright_red("tile_upgrade", "No more upgrades available for this tile");
+                return
             };
 
+            // Higher levels need to be researched first
+            let locked = Some(tile.tile_level + 1)
+                .filter(|level| !planet.research.is_upgrade_unlocked(*level))
+                .and_then(Research::required_for_upgrade);
+            if let Some(technology) = locked {
+                logger::log::bright_red("tile_upgrade", format!("{} isn't researched", technology.display_name()));
+                world.commands().queue(SpawnInfoText(format!("Research {} first", technology.display_name())));
+                return
+            }
+
+            // Upgrades that make the tile wider need room next to it
+            let mut upgraded = tile.clone();
+            upgraded.tile_level += 1;
+            if upgraded.width() > tile.width()
+                && !TileSpawnPlugin::tile_fits(&planet, &upgraded.width(), tile_id, Some(tile_id)) {
+                logger::log::bright_red("tile_upgrade", "Not enough room to upgrade this tile");
+                return
+            }
+
             // Try buy upgrade
-            match planet.resources.try_spend(&upgrade_costs) {
+            match planet.resources.try_spend(&upgrade.cost) {
                 Ok(_) => {},
                 Err(e) => {
                     logger::log::bright_red("tile_upgrade", &e);
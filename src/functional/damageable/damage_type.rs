@@ -0,0 +1,84 @@
+/* Imports */
+use bevy::utils::HashMap;
+use crate::utils::audio::game_sounds;
+
+/// How something is hit, targets resist
+/// some damage types better than others
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum DamageType {
+    Blunt,
+    Cutting,
+    Energy,
+}
+
+impl DamageType {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Blunt => "Blunt",
+            Self::Cutting => "Cutting",
+            Self::Energy => "Energy",
+        }
+    }
+}
+
+/// How much of each damage type a target blocks,
+/// 0.0 = takes full damage, 1.0 = immune
+#[derive(Clone, Debug, Default)]
+pub struct Resistances(HashMap<DamageType, f32>);
+
+impl Resistances {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, damage_type: DamageType, resistance: f32) -> Self {
+        self.0.insert(damage_type, resistance.clamp(0.0, 1.0));
+        self
+    }
+
+    /// How much damage gets through
+    pub fn apply(&self, damage_type: DamageType, damage: f32) -> f32 {
+        damage * (1.0 - self.0.get(&damage_type).cloned().unwrap_or(0.0))
+    }
+
+    pub fn is_immune(&self, damage_type: DamageType) -> bool {
+        self.apply(damage_type, 1.0) <= 0.0
+    }
+}
+
+/// What a target is made of, decides how hitting it sounds
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HitMaterial {
+    Wood,
+    Stone,
+    Flesh,
+}
+
+impl HitMaterial {
+    pub fn hit_sounds(&self) -> &'static [&'static str] {
+        match self {
+            Self::Wood => game_sounds::tree::DAMAGE,
+            Self::Stone => game_sounds::stone::DAMAGE,
+            // No sounds of its own yet
+            Self::Flesh => game_sounds::tree::DAMAGE,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resistance_scales_damage() {
+        let resistances = Resistances::none()
+            .with(DamageType::Blunt, 0.25)
+            .with(DamageType::Energy, 2.0);
+
+        assert_eq!(resistances.apply(DamageType::Blunt, 20.0), 15.0);
+        assert_eq!(resistances.apply(DamageType::Cutting, 20.0), 20.0);
+        // Clamped to immune
+        assert_eq!(resistances.apply(DamageType::Energy, 20.0), 0.0);
+        assert!(resistances.is_immune(DamageType::Energy));
+    }
+}
@@ -3,17 +3,22 @@ use std::f32::consts::PI;
 /* Imports */
 use bevy::{audio::PlaybackMode, prelude::*, utils::HashMap};
 use rand::Rng;
-use crate::{components::planet::{Planet, PlayerPlanet}, systems::game::PlanetResource, ui::info_text::SpawnInfoText, utils::{audio::{game_sounds, play_audio, PlayAudioEvent}, color::hex, logger}};
+use crate::{components::{creature::Creature, planet::{Planet, PlayerPlanet}, player::{health::PlayerHealth, player::Player, tool::{poi_type_of, ToolBelt}}, poi::{copper::Copper, stone::Stone, tree::Tree}}, ui::info_text::SpawnInfoText, utils::{audio::{play_audio, PlayAudioEvent}, color::hex, logger}};
+use super::{DamageType, HitMaterial, LootTable, Resistances};
 
 /// Some component that can be damaged
 #[derive(Component)]
 pub struct Damageable {
     pub health: f32,
     pub max_health: f32,
-    pub flash_timer: Timer,
-    pub callback: fn(&mut World) -> (),
+    pub resistances: Resistances,
+    pub material: HitMaterial,
 
-    drop: Option<(PlanetResource, usize)>
+    /// Called right before the entity is despawned,
+    /// with the entity and its position in the world
+    pub callback: fn(&mut World, Entity, Vec2) -> (),
+
+    loot: LootTable,
 }
 
 /// This stores all damages done for entites,
@@ -26,27 +31,55 @@ pub struct DamageLabels {
     pub labels: HashMap<Entity, (Entity, f32)>,
 }
 
-/// Component for damage impact effect 
+/// Component for damage impact effect, tints the sprite
+/// of the entity (and its children) until the timer runs out
 #[derive(Component)]
-pub struct Flashing;
+pub struct Flashing {
+    timer: Timer,
 
-/// Event for damaging entities
+    /// The colors the sprites had before flashing,
+    /// restored when the flash is over
+    original: HashMap<Entity, Color>,
+}
+
+impl Flashing {
+    pub fn new() -> Self {
+        Self { timer: Timer::from_seconds(0.1, TimerMode::Once), original: HashMap::default() }
+    }
+}
+
+/// Event for damaging entities, the damage
+/// is lowered by the resistances of the target
 #[derive(Event)]
 pub struct DamageEvent {
     pub target_entity: Entity,
     pub damage: f32,
+    pub damage_type: DamageType,
 }
 
 impl Damageable {
-    /// drop: (resource, amount)
-    pub fn new(max_health: f32, drop: Option<(PlanetResource, usize)>, callback: fn(&mut World) -> ()) -> Self {
+    pub fn new(max_health: f32, material: HitMaterial, callback: fn(&mut World, Entity, Vec2) -> ()) -> Self {
         Self {
-            health: max_health, max_health, drop,
-            flash_timer: Timer::from_seconds(0.1, TimerMode::Once),
+            health: max_health, max_health, material,
+            resistances: Resistances::none(),
+            loot: LootTable::default(),
             callback
         }
     }
 
+    /// What is dropped when destroyed
+    pub fn with_loot(mut self, loot: LootTable) -> Self {
+        self.loot = loot;
+        self
+    }
+
+    pub fn with_resistances(mut self, resistances: Resistances) -> Self {
+        self.resistances = resistances;
+        self
+    }
+
+    /// Hits the entity with the tool the player has selected,
+    /// if it's within reach
     pub fn on_clicked(
         click: Trigger<Pointer<Down>>,
         mut commands: Commands,
@@ -55,13 +88,34 @@ impl Damageable {
         mut damage_text_q: Query<&mut AnimatedDamageText>,
         asset_server: Res<AssetServer>,
         mut audio_events: EventWriter<PlayAudioEvent>,
+        mut player_q: Query<(&mut Player, &mut ToolBelt, &PlayerHealth)>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        poi_q: Query<(Option<&Tree>, Option<&Stone>, Option<&Copper>)>,
+        creature_q: Query<(), With<Creature>>,
+        target_q: Query<(&Damageable, &GlobalTransform)>,
     ) {
         let target_entity = click.entity();
-        let damage = (rand::random::<f32>() * 5.0 + 3.0).floor();
-        damage_events.send(DamageEvent { target_entity, damage });
+        let Ok((mut player, mut tool_belt, health)) = player_q.get_single_mut() else { return };
+        if health.is_dead() { return }
+        let Ok(planet) = planet_q.get_single() else { return };
+        let Some(target_name) = poi_type_of(target_entity, &poi_q).map(|poi_type| poi_type.display_name())
+            .or_else(|| creature_q.contains(target_entity).then_some("Creature")) else { return };
+        let Ok((damageable, target_transform)) = target_q.get(target_entity) else { return };
+
+        let position = target_transform.translation().truncate();
+        let (damage, damage_type) = match tool_belt.try_use(&mut player, target_name, &damageable.resistances, position.y.atan2(position.x), planet) {
+            Ok(Some((damage, damage_type))) => (damage * rand::thread_rng().gen_range(0.8..1.2), damage_type),
+            Ok(None) => return, // Cooling down
+            Err(e) => {
+                commands.queue(SpawnInfoText(e));
+                return;
+            },
+        };
+        damage_events.send(DamageEvent { target_entity, damage, damage_type });
+        let damage = damageable.resistances.apply(damage_type, damage).round().max(1.0);
 
         /* Visual */
-        commands.entity(click.entity()).insert(Flashing);
+        commands.entity(click.entity()).insert_if_new(Flashing::new());
         AnimatedDamageText::spawn_damage_text(
             &mut commands,
             &asset_server,
@@ -71,7 +125,7 @@ impl Damageable {
             damage
         );
         play_audio(
-            game_sounds::tree::DAMAGE,
+            damageable.material.hit_sounds(),
             PlaybackSettings {
                 mode: bevy::audio::PlaybackMode::Despawn,
                 spatial: true,
@@ -92,31 +146,37 @@ impl Damageable {
         // Collect entities to process
         let mut entities_to_process = Vec::new();
         for event in reader.read(&damage_events) {
-            entities_to_process.push((event.target_entity, event.damage));
+            entities_to_process.push((event.target_entity, event.damage, event.damage_type));
         }
         damage_events.clear();
     
         // Process entities
-        for (entity, damage) in entities_to_process {
-            let mut callback = None;
+        for (entity, damage, damage_type) in entities_to_process {
             let Ok(mut entity_mut) = world.get_entity_mut(entity) else { continue; };
+            let position = entity_mut.get::<GlobalTransform>()
+                .map(|transform| transform.translation().truncate())
+                .unwrap_or_default();
             let Some(mut damageable) = entity_mut.get_mut::<Damageable>() else { continue; };
 
+            let damage = damageable.resistances.apply(damage_type, damage);
             damageable.health -= damage;
-            if damageable.health <= 0.0 {
-                let drop = damageable.drop.clone();
-                callback = Some(damageable.callback);
+            if damageable.health > 0.0 { continue }
+
+            let drops = damageable.loot.roll(&mut rand::thread_rng());
+            let callback = damageable.callback;
+            (callback)(world, entity, position);
+            if let Ok(entity_mut) = world.get_entity_mut(entity) {
                 entity_mut.despawn_recursive();
-            
-                let Some((resource, amount)) = drop else { continue; };
-                let Ok(mut planet) = world.query_filtered::<&mut Planet, With<PlayerPlanet>>().get_single_mut(world) else { continue; };
-                planet.resources.add(resource, amount);
-                world.commands().queue(SpawnInfoText(format!("{}x {}", amount, format!("{:?}", resource).to_ascii_lowercase())));
-                logger::log::bright_green("resource", format!("Dropped {} x{}", format!("{:?}", resource).to_ascii_lowercase(), amount));
             }
 
-            if let Some(callback) = callback {
-                (callback)(world);
+            let Ok(mut planet) = world.query_filtered::<&mut Planet, With<PlayerPlanet>>().get_single_mut(world) else { continue; };
+            for (resource, amount) in drops.iter() {
+                planet.resources.add(*resource, *amount);
+            }
+            for (resource, amount) in drops {
+                let name = format!("{:?}", resource).to_ascii_lowercase();
+                world.commands().queue(SpawnInfoText(format!("{}x {}", amount, name)));
+                logger::log::bright_green("resource", format!("Dropped {} x{}", name, amount));
             }
         }
     }
@@ -124,16 +184,21 @@ impl Damageable {
     // Flash effect system
     fn handle_flashing(
         mut commands: Commands,
-        mut query: Query<(Entity, &mut Damageable, &mut Sprite), With<Flashing>>,
+        mut query: Query<(Entity, &mut Flashing, Option<&Children>)>,
+        mut sprite_q: Query<&mut Sprite>,
         time: Res<Time>
     ) {
-        for (entity, mut damageable, mut sprite) in query.iter_mut() {
-            if damageable.flash_timer.tick(time.delta()).finished() {
-                damageable.flash_timer.reset();
+        for (entity, mut flashing, children) in query.iter_mut() {
+            let finished = flashing.timer.tick(time.delta()).finished();
+            if finished {
                 commands.entity(entity).remove::<Flashing>();
-                sprite.color = Color::WHITE;
-            } else {
-                sprite.color = Color::srgba(255., 255., 255., 1.);
+            }
+
+            let children = children.map(|children| children.to_vec()).unwrap_or_default();
+            for target in std::iter::once(entity).chain(children) {
+                let Ok(mut sprite) = sprite_q.get_mut(target) else { continue };
+                let original = *flashing.original.entry(target).or_insert(sprite.color);
+                sprite.color = if finished { original } else { Color::srgba(255., 255., 255., 1.) };
             }
         }
     }
@@ -149,7 +214,7 @@ pub struct AnimatedDamageText {
 }
 
 impl AnimatedDamageText {
-    fn spawn_damage_text(
+    pub(crate) fn spawn_damage_text(
         commands: &mut Commands,
         asset_server: &Res<AssetServer>,
         damage_labels: &mut ResMut<DamageLabels>,
@@ -0,0 +1,91 @@
+/* Imports */
+use std::ops::RangeInclusive;
+use bevy::utils::HashMap;
+use rand::Rng;
+use crate::systems::game::PlanetResource;
+
+/// One possible drop of a loot table
+#[derive(Clone, Debug)]
+struct LootEntry {
+    weight: u32,
+    resource: PlanetResource,
+    amount: RangeInclusive<usize>,
+}
+
+/// What something drops when destroyed. Every roll picks
+/// one entry by weight, so rarer drops get a lower weight.
+#[derive(Clone, Debug, Default)]
+pub struct LootTable {
+    rolls: usize,
+    entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub fn new(rolls: usize) -> Self {
+        Self { rolls, entries: Vec::new() }
+    }
+
+    pub fn with(mut self, weight: u32, resource: PlanetResource, amount: RangeInclusive<usize>) -> Self {
+        self.entries.push(LootEntry { weight, resource, amount });
+        self
+    }
+
+    /// Rolls the table, amounts of the same
+    /// resource are added together
+    pub fn roll(&self, rng: &mut impl Rng) -> Vec<(PlanetResource, usize)> {
+        let total_weight: u32 = self.entries.iter().map(|entry| entry.weight).sum();
+        if total_weight == 0 { return Vec::new() }
+
+        let mut drops: HashMap<PlanetResource, usize> = HashMap::new();
+        for _ in 0..self.rolls {
+            let mut pick = rng.gen_range(0..total_weight);
+            let Some(entry) = self.entries.iter().find(|entry| {
+                if pick < entry.weight { return true }
+                pick -= entry.weight;
+                false
+            }) else { continue };
+
+            *drops.entry(entry.resource).or_insert(0) += rng.gen_range(entry.amount.clone());
+        }
+
+        PlanetResource::ALL.iter()
+            .filter_map(|resource| drops.get(resource).map(|amount| (*resource, *amount)))
+            .filter(|(_, amount)| *amount > 0)
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    #[test]
+    fn roll_single_entry() {
+        // Only one entry, it's dropped on every roll
+        let table = LootTable::new(3).with(1, PlanetResource::Wood, 2..=2);
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert_eq!(table.roll(&mut rng), vec![(PlanetResource::Wood, 6)]);
+    }
+
+    #[test]
+    fn roll_by_weight() {
+        let table = LootTable::new(4000)
+            .with(3, PlanetResource::Wood, 1..=1)
+            .with(1, PlanetResource::Stone, 1..=1)
+            .with(0, PlanetResource::Copper, 1..=1);
+        let mut rng = ChaCha8Rng::seed_from_u64(1);
+        let drops: HashMap<PlanetResource, usize> = table.roll(&mut rng).into_iter().collect();
+
+        assert_eq!(drops[&PlanetResource::Wood] + drops[&PlanetResource::Stone], 4000);
+        assert!(drops[&PlanetResource::Wood] > drops[&PlanetResource::Stone] * 2);
+        assert!(!drops.contains_key(&PlanetResource::Copper));
+    }
+
+    #[test]
+    fn roll_empty_table() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        assert!(LootTable::new(5).roll(&mut rng).is_empty());
+    }
+}
@@ -1,3 +1,6 @@
 mod damageable;
 pub use damageable::*;
-
+mod damage_type;
+pub use damage_type::*;
+mod loot;
+pub use loot::*;
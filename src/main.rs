@@ -15,7 +15,7 @@ use bevy::{
 use camera::background::background::BackgroundPlugin;
 use functional::damageable;
 use systems::game;
-use components::{cable::{cable, slot}, foliage::animation::FoliageAnimationPlugin, planet, player::player::PlayerPlugin, poi::PointOfInterestPlugin, tile};
+use components::{cable::{cable, slot}, creature, foliage::animation::FoliageAnimationPlugin, planet, player::player::PlayerPlugin, poi::PointOfInterestPlugin, tile};
 use utils::{audio::GameAudioPlugin, color::hex};
 
 /// In-game resolution width.
@@ -56,7 +56,12 @@ fn main() {
 
         /* Important plugins */
         .add_plugins((
+            game::AppStatePlugin,
             game::GameTickPlugin,
+            game::StatsHistoryPlugin,
+            game::ResearchPlugin,
+            game::ObjectivesPlugin,
+            game::ProfilePlugin,
             camera::CameraPlugin,
             GameAudioPlugin,
         ))
@@ -71,14 +76,24 @@ fn main() {
             game::GamePlugin,
             damageable::DamageablePlugin,
             FoliageAnimationPlugin,
+            PointOfInterestPlugin,
+            tile::TilePlugin,
+            PlayerPlugin,
+            planet::disaster::DisasterPlugin,
+            creature::CreaturePlugin,
+        ))
 
+        /* UI */
+        .add_plugins((
             ui::hud::HudPlugin,
             ui::stats::StatsPlugin,
             ui::inventory::InventoryPlugin,
             ui::info_text::InfoTextPlugin,
-            PointOfInterestPlugin,
-            tile::TilePlugin,
-            PlayerPlugin,
+            ui::power_overlay::PowerOverlayPlugin,
+            ui::graph::GraphPlugin,
+            ui::objectives::ObjectiveTrackerPlugin,
+            ui::menu::MenuPlugin,
+            ui::world_setup::WorldSetupPlugin,
         ))
 
         /* Debug */
@@ -4,3 +4,13 @@ mod resources;
 pub use resources::*;
 mod tick;
 pub use tick::*;
+mod stats;
+pub use stats::*;
+mod research;
+pub use research::*;
+mod objectives;
+pub use objectives::*;
+mod profile;
+pub use profile::*;
+mod state;
+pub use state::*;
@@ -0,0 +1,200 @@
+/* Imports */
+use bevy::{prelude::*, utils::HashMap};
+use crate::{
+    components::{
+        planet::{Planet, PlayerPlanet},
+        tile::{types::{power_pole::PowerPole, research_lab::ResearchLab, solar_panel::SolarPanel, turret::EnergyTurret}, TileType},
+    },
+    systems::traits::GenericTile,
+    ui::info_text::SpawnInfoText,
+    utils::{logger, save::{SaveFile, SAVE_PATH}},
+};
+use super::{AppState, GameTickSet, PlanetResource, Technology};
+
+/// Something the player should do, checked against the planet every tick
+#[derive(Clone, Debug)]
+pub enum Goal {
+    /// Have this many tiles of a type
+    Build(TileType, usize),
+    /// Have this many cables
+    ConnectCables(usize),
+    /// Upgrade any tile
+    Upgrade,
+    /// Have this much energy stored in all tiles together
+    StoreEnergy(f32),
+    /// Gain this much of a resource after the objective started
+    Collect(PlanetResource, usize),
+    Research(Technology),
+}
+
+impl Goal {
+    pub fn description(&self) -> String {
+        match self {
+            Self::Build(tile_type, 1) => format!("Build a {}", tile_type.display_name().to_lowercase()),
+            Self::Build(tile_type, amount) => format!("Build {} {}s", amount, tile_type.display_name().to_lowercase()),
+            Self::ConnectCables(1) => "Connect two tiles with a cable".to_string(),
+            Self::ConnectCables(amount) => format!("Lay {} cables", amount),
+            Self::Upgrade => "Upgrade a tile".to_string(),
+            Self::StoreEnergy(amount) => format!("Store {:.0} energy", amount),
+            Self::Collect(resource, amount) => format!("Collect {} {}", amount, format!("{:?}", resource).to_lowercase()),
+            Self::Research(technology) => format!("Research {}", technology.display_name().to_lowercase()),
+        }
+    }
+
+    /// How far the goal is, as (done, needed)
+    pub fn progress(&self, planet: &Planet, produced_at_start: &HashMap<PlanetResource, usize>) -> (f32, f32) {
+        match self {
+            Self::Build(tile_type, amount) => {
+                let built = planet.tiles.values().filter(|tile| tile.tile_type == *tile_type).count();
+                (built as f32, *amount as f32)
+            },
+            Self::ConnectCables(amount) => {
+                // Every cable is in the connections of both of its ends
+                let ends: usize = planet.tiles.values().map(|tile| tile.powergrid_status.connected_tiles.len()).sum();
+                ((ends / 2) as f32, *amount as f32)
+            },
+            Self::Upgrade => {
+                let upgraded = planet.tiles.values().any(|tile| tile.tile_level > 0);
+                (if upgraded { 1.0 } else { 0.0 }, 1.0)
+            },
+            Self::StoreEnergy(amount) => {
+                let stored: f32 = planet.tiles.values().map(|tile| tile.powergrid_status.energy_stored).sum();
+                (stored, *amount)
+            },
+            Self::Collect(resource, amount) => {
+                let start = produced_at_start.get(resource).cloned().unwrap_or(0);
+                let collected = planet.resources.total_produced(*resource).saturating_sub(start);
+                (collected as f32, *amount as f32)
+            },
+            Self::Research(technology) => {
+                (if planet.research.is_unlocked(*technology) { 1.0 } else { 0.0 }, 1.0)
+            },
+        }
+    }
+}
+
+/// A goal with a reward, tutorial steps also
+/// have a hint on how to do it
+#[derive(Clone, Debug)]
+pub struct Objective {
+    pub goal: Goal,
+    pub hint: Option<&'static str>,
+    pub reward: Vec<(PlanetResource, usize)>,
+}
+
+impl Objective {
+    pub fn new(goal: Goal, reward: Vec<(PlanetResource, usize)>) -> Self {
+        Self { goal, hint: None, reward }
+    }
+
+    pub fn with_hint(mut self, hint: &'static str) -> Self {
+        self.hint = Some(hint);
+        self
+    }
+
+    /// Every objective, in the order they're given. The
+    /// first ones are the tutorial.
+    pub fn all() -> Vec<Objective> {
+        use PlanetResource::*;
+        vec![
+            /* Tutorial */
+            Objective::new(Goal::Build(TileType::PowerPole(PowerPole), 1), vec![(Wood, 10)])
+                .with_hint("Press Q and click on the ground to build a power pole"),
+            Objective::new(Goal::Build(TileType::SolarPanel(SolarPanel), 1), vec![(Wood, 10)])
+                .with_hint("Press E to pick a solar panel, then click to place it"),
+            Objective::new(Goal::ConnectCables(1), vec![(Stone, 10)])
+                .with_hint("Click the cable slot of a tile, then the slot of another one"),
+            Objective::new(Goal::Build(TileType::ResearchLab(ResearchLab::default()), 1), vec![(Stone, 10)])
+                .with_hint("Press H to build a research lab, and give it power"),
+            Objective::new(Goal::Upgrade, vec![(Copper, 10)])
+                .with_hint("Click on a tile to inspect it, then press Upgrade"),
+
+            /* Goals */
+            Objective::new(Goal::Build(TileType::SolarPanel(SolarPanel), 3), vec![(Stone, 20)]),
+            Objective::new(Goal::Research(Technology::Storage), vec![(Copper, 10)]),
+            Objective::new(Goal::StoreEnergy(100.0), vec![(Copper, 20)]),
+            Objective::new(Goal::Collect(Copper, 50), vec![(Wood, 30), (Stone, 30)]),
+            Objective::new(Goal::Build(TileType::EnergyTurret(EnergyTurret), 1), vec![(Copper, 20)]),
+        ]
+    }
+}
+
+/// Which objective the player is on
+#[derive(Resource, Default)]
+pub struct Objectives {
+    /// Index into [`Objective::all`], past the end when everything is done
+    pub current: usize,
+
+    /// How much of every resource had been produced when
+    /// the current objective started, for collect goals
+    pub produced_at_start: HashMap<PlanetResource, usize>,
+}
+
+impl Objectives {
+    pub fn current_objective(&self) -> Option<Objective> {
+        Objective::all().get(self.current).cloned()
+    }
+
+    /// Collect goals only count what's gained from now on
+    fn start(&mut self, index: usize, planet: &Planet) -> () {
+        self.current = index;
+        self.produced_at_start = PlanetResource::ALL.iter()
+            .map(|resource| (*resource, planet.resources.total_produced(*resource)))
+            .collect();
+    }
+
+    /// Objectives done on another world (seed) don't count
+    fn setup(mut commands: Commands, planet_q: Query<&Planet, With<PlayerPlanet>>) -> () {
+        let save = SaveFile::load(SAVE_PATH);
+        let mut objectives = Self::default();
+        if let Ok(planet) = planet_q.get_single() {
+            let completed = save.get_parsed::<u32>("objectives.seed")
+                .filter(|seed| *seed == planet.seed)
+                .and_then(|_| save.get_parsed("objectives.completed"))
+                .unwrap_or(0);
+            objectives.start(completed, planet);
+        }
+        commands.insert_resource(objectives);
+    }
+
+    /// Gives the reward and moves on to the next
+    /// objective when the current one is done
+    fn tick(
+        mut commands: Commands,
+        mut objectives: ResMut<Objectives>,
+        mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+    ) -> () {
+        let Ok(mut planet) = planet_q.get_single_mut() else { return };
+        let Some(objective) = objectives.current_objective() else { return };
+        let (done, needed) = objective.goal.progress(&planet, &objectives.produced_at_start);
+        if done < needed { return }
+
+        for (resource, amount) in &objective.reward {
+            planet.resources.add(*resource, *amount);
+        }
+        let reward: Vec<String> = objective.reward.iter()
+            .map(|(resource, amount)| format!("{amount} {resource:?}"))
+            .collect();
+        logger::log::bright_green("objectives", format!("Completed \"{}\"", objective.goal.description()));
+        commands.queue(SpawnInfoText(format!("{} - done! +{}", objective.goal.description(), reward.join(", "))));
+
+        let next = objectives.current + 1;
+        objectives.start(next, &planet);
+        let mut save = SaveFile::load(SAVE_PATH);
+        save.set("objectives.seed", planet.seed);
+        save.set("objectives.completed", objectives.current);
+        if let Err(e) = save.write() {
+            logger::log::bright_red("objectives", e);
+        }
+    }
+}
+
+pub struct ObjectivesPlugin;
+impl Plugin for ObjectivesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Objectives>()
+            .add_systems(OnEnter(AppState::Loading), Objectives::setup.after(Planet::setup))
+            .add_systems(FixedUpdate, Objectives::tick.in_set(GameTickSet));
+    }
+}
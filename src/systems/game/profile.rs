@@ -0,0 +1,181 @@
+/* Imports */
+use bevy::{prelude::*, utils::{HashMap, HashSet}};
+use crate::{
+    components::planet::{Planet, PlayerPlanet},
+    ui::info_text::SpawnInfoText,
+    utils::{logger, save::{SaveFile, PROFILE_PATH}},
+};
+use super::GameTickSet;
+
+/* Constants */
+/// How often the profile is written while stats are changing
+const SAVE_INTERVAL_TICKS: u32 = 20;
+
+/// Something counted over every game played on this device
+/// * Important: Don't forget to add new variants to `LifetimeStat::ALL`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LifetimeStat {
+    TreesFelled,
+    TilesPlaced,
+    EnergyGenerated,
+    CablesLaid,
+}
+
+impl LifetimeStat {
+    pub const ALL: [LifetimeStat; 4] = [
+        Self::TreesFelled, Self::TilesPlaced,
+        Self::EnergyGenerated, Self::CablesLaid,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::TreesFelled => "Trees felled",
+            Self::TilesPlaced => "Tiles placed",
+            Self::EnergyGenerated => "Energy generated",
+            Self::CablesLaid => "Cables laid",
+        }
+    }
+
+    /// Name in the profile file
+    fn key(&self) -> &'static str {
+        match self {
+            Self::TreesFelled => "trees_felled",
+            Self::TilesPlaced => "tiles_placed",
+            Self::EnergyGenerated => "energy_generated",
+            Self::CablesLaid => "cables_laid",
+        }
+    }
+}
+
+/// Unlocked once a lifetime stat reaches `threshold`
+pub struct Achievement {
+    /// Name in the profile file, never change it
+    /// or the achievement is lost for everyone
+    pub key: &'static str,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub stat: LifetimeStat,
+    pub threshold: f32,
+}
+
+pub const ACHIEVEMENTS: [Achievement; 8] = [
+    Achievement { key: "first_tree", name: "Lumberjack", description: "Fell a tree",
+        stat: LifetimeStat::TreesFelled, threshold: 1.0 },
+    Achievement { key: "deforestation", name: "Deforestation", description: "Fell 100 trees",
+        stat: LifetimeStat::TreesFelled, threshold: 100.0 },
+    Achievement { key: "builder", name: "Builder", description: "Place 10 tiles",
+        stat: LifetimeStat::TilesPlaced, threshold: 10.0 },
+    Achievement { key: "architect", name: "Architect", description: "Place 250 tiles",
+        stat: LifetimeStat::TilesPlaced, threshold: 250.0 },
+    Achievement { key: "electrician", name: "Electrician", description: "Lay 10 cables",
+        stat: LifetimeStat::CablesLaid, threshold: 10.0 },
+    Achievement { key: "grid_operator", name: "Grid operator", description: "Lay 100 cables",
+        stat: LifetimeStat::CablesLaid, threshold: 100.0 },
+    Achievement { key: "spark", name: "Spark", description: "Generate 1 000 energy",
+        stat: LifetimeStat::EnergyGenerated, threshold: 1_000.0 },
+    Achievement { key: "power_plant", name: "Power plant", description: "Generate 1 000 000 energy",
+        stat: LifetimeStat::EnergyGenerated, threshold: 1_000_000.0 },
+];
+
+/// Lifetime stats and achievements, kept in their own file
+/// so that they carry over between games
+#[derive(Resource, Default)]
+pub struct Profile {
+    stats: HashMap<LifetimeStat, f32>,
+    unlocked: HashSet<&'static str>,
+
+    /// If something changed since the profile was written
+    dirty: bool,
+}
+
+impl Profile {
+    pub fn get(&self, stat: LifetimeStat) -> f32 {
+        self.stats.get(&stat).cloned().unwrap_or(0.0)
+    }
+
+    pub fn add(&mut self, stat: LifetimeStat, amount: f32) -> () {
+        if amount <= 0.0 { return }
+        *self.stats.entry(stat).or_insert(0.0) += amount;
+        self.dirty = true;
+    }
+
+    pub fn is_unlocked(&self, achievement: &Achievement) -> bool {
+        self.unlocked.contains(achievement.key)
+    }
+
+    fn load(save: &SaveFile) -> Self {
+        let stats = LifetimeStat::ALL.iter()
+            .filter_map(|stat| Some((*stat, save.get_parsed(&format!("stats.{}", stat.key()))?)))
+            .collect();
+        let unlocked = ACHIEVEMENTS.iter()
+            .filter(|achievement| save.get(&format!("achievements.{}", achievement.key)) == Some("true"))
+            .map(|achievement| achievement.key)
+            .collect();
+
+        Self { stats, unlocked, dirty: false }
+    }
+
+    fn store(&self, save: &mut SaveFile) -> () {
+        for stat in LifetimeStat::ALL {
+            save.set(&format!("stats.{}", stat.key()), self.get(stat));
+        }
+        for achievement in ACHIEVEMENTS.iter().filter(|achievement| self.is_unlocked(achievement)) {
+            save.set(&format!("achievements.{}", achievement.key), true);
+        }
+    }
+
+    fn setup(mut commands: Commands) -> () {
+        let profile = Self::load(&SaveFile::load(PROFILE_PATH));
+        logger::log::cyan("profile", format!("Loaded profile with {}/{} achievements",
+            profile.unlocked.len(), ACHIEVEMENTS.len()));
+        commands.insert_resource(profile);
+    }
+
+    /// Counts the energy generated since the last tick, unlocks
+    /// achievements and writes the profile now and then
+    fn tick(
+        mut commands: Commands,
+        mut profile: ResMut<Profile>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        mut last_generated: Local<Option<f32>>,
+        mut ticks: Local<u32>,
+    ) -> () {
+        if let Ok(planet) = planet_q.get_single() {
+            // A regenerated planet starts over at 0
+            let last = last_generated.unwrap_or(planet.energy_generated).min(planet.energy_generated);
+            profile.add(LifetimeStat::EnergyGenerated, planet.energy_generated - last);
+            *last_generated = Some(planet.energy_generated);
+        }
+
+        let mut unlocked_any = false;
+        for achievement in ACHIEVEMENTS.iter() {
+            if profile.is_unlocked(achievement) || profile.get(achievement.stat) < achievement.threshold { continue }
+            profile.unlocked.insert(achievement.key);
+            profile.dirty = true;
+            unlocked_any = true;
+
+            logger::log::bright_green("profile", format!("Achievement unlocked: {}", achievement.name));
+            commands.queue(SpawnInfoText(format!("Achievement unlocked: {} ({})",
+                achievement.name, achievement.description.to_lowercase())));
+        }
+
+        *ticks = ticks.wrapping_add(1);
+        if !profile.dirty || !(unlocked_any || *ticks % SAVE_INTERVAL_TICKS == 0) { return }
+        let mut save = SaveFile::load(PROFILE_PATH);
+        profile.store(&mut save);
+        match save.write() {
+            Ok(_) => profile.dirty = false,
+            Err(e) => logger::log::bright_red("profile", e),
+        }
+    }
+}
+
+pub struct ProfilePlugin;
+impl Plugin for ProfilePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<Profile>()
+            .add_systems(Startup, Profile::setup)
+            .add_systems(FixedUpdate, Profile::tick.in_set(GameTickSet));
+    }
+}
@@ -0,0 +1,344 @@
+/* Imports */
+use bevy::{prelude::*, utils::HashSet};
+use crate::{
+    components::{
+        planet::{Planet, PlayerPlanet},
+        tile::{types::{battery::Battery, drill::Drill, loudspeaker::Loudspeaker, sensor::Sensor, switch::{Breaker, Switch}, turret::EnergyTurret, wind_turbine::WindTurbine}, TileType},
+    },
+    ui::info_text::SpawnInfoText,
+    utils::{logger, save::{SaveFile, SAVE_PATH}},
+};
+use super::{AppState, GameTickSet};
+
+/* Constants */
+/// Upgrade levels up to this one need no research
+const FREE_UPGRADE_LEVELS: usize = 1;
+/// How often research progress is written while labs are working
+const SAVE_INTERVAL_TICKS: u32 = 20;
+
+/// Something that can be researched. Technologies unlock
+/// tiles and upgrade levels, and need others to be
+/// researched before them.
+/// * Important: Don't forget to add new variants to `Technology::ALL`
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Technology {
+    Storage,
+    WindPower,
+    Mining,
+    Automation,
+    Refinement,
+    Defense,
+    Engineering,
+}
+
+impl Technology {
+    /// Every technology, in the order they're picked for research
+    pub const ALL: [Technology; 7] = [
+        Self::Storage, Self::WindPower, Self::Mining, Self::Automation,
+        Self::Refinement, Self::Defense, Self::Engineering,
+    ];
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Storage => "Storage",
+            Self::WindPower => "Wind power",
+            Self::Mining => "Mining",
+            Self::Automation => "Automation",
+            Self::Refinement => "Refinement",
+            Self::Defense => "Defense",
+            Self::Engineering => "Engineering",
+        }
+    }
+
+    /// Name in the save file
+    fn key(&self) -> &'static str {
+        match self {
+            Self::Storage => "storage",
+            Self::WindPower => "wind_power",
+            Self::Mining => "mining",
+            Self::Automation => "automation",
+            Self::Refinement => "refinement",
+            Self::Defense => "defense",
+            Self::Engineering => "engineering",
+        }
+    }
+
+    fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|technology| technology.key() == key)
+    }
+
+    /// Research points needed
+    pub fn cost(&self) -> f32 {
+        match self {
+            Self::Storage => 20.0,
+            Self::WindPower => 25.0,
+            Self::Mining => 30.0,
+            Self::Automation => 40.0,
+            Self::Refinement => 40.0,
+            Self::Defense => 50.0,
+            Self::Engineering => 80.0,
+        }
+    }
+
+    /// What needs to be researched first
+    pub fn requires(&self) -> &'static [Technology] {
+        match self {
+            Self::Storage | Self::WindPower | Self::Mining => &[],
+            Self::Automation => &[Self::Storage],
+            Self::Refinement => &[Self::Mining],
+            Self::Defense => &[Self::Automation],
+            Self::Engineering => &[Self::Refinement, Self::Automation],
+        }
+    }
+
+    /// Tiles that can't be built before this is researched
+    pub fn unlocks_tiles(&self) -> Vec<TileType> {
+        match self {
+            Self::Storage => vec![TileType::Battery(Battery)],
+            Self::WindPower => vec![TileType::WindTurbine(WindTurbine)],
+            Self::Mining => vec![TileType::Drill(Drill::default())],
+            Self::Automation => vec![
+                TileType::Switch(Switch),
+                TileType::Breaker(Breaker),
+                TileType::Sensor(Sensor::default()),
+                TileType::Loudspeaker(Loudspeaker),
+            ],
+            Self::Defense => vec![TileType::EnergyTurret(EnergyTurret)],
+            Self::Refinement | Self::Engineering => Vec::new(),
+        }
+    }
+
+    /// The highest upgrade level this lets tiles reach
+    pub fn unlocks_upgrade_level(&self) -> Option<usize> {
+        match self {
+            Self::Refinement => Some(2),
+            Self::Engineering => Some(usize::MAX),
+            _ => None,
+        }
+    }
+}
+
+/// What has been researched and what is being researched.
+/// Research points are made by research labs.
+#[derive(Debug, Clone, Default)]
+pub struct Research {
+    unlocked: HashSet<Technology>,
+
+    /// What research points go to, `None` when
+    /// everything that can be researched is done
+    current: Option<Technology>,
+    /// Research points put into `current`
+    progress: f32,
+
+    /// If something changed since the last save
+    dirty: bool,
+}
+
+impl Research {
+    pub fn is_unlocked(&self, technology: Technology) -> bool {
+        self.unlocked.contains(&technology)
+    }
+
+    /// Technologies that aren't researched but
+    /// have everything they require
+    pub fn available(&self) -> Vec<Technology> {
+        Technology::ALL.into_iter()
+            .filter(|technology| !self.is_unlocked(*technology))
+            .filter(|technology| technology.requires().iter().all(|required| self.is_unlocked(*required)))
+            .collect()
+    }
+
+    /// The technology that unlocks a tile, `None` if
+    /// it's available from the start
+    pub fn required_for_tile(tile_type: &TileType) -> Option<Technology> {
+        Technology::ALL.into_iter()
+            .find(|technology| technology.unlocks_tiles().contains(tile_type))
+    }
+
+    pub fn is_tile_unlocked(&self, tile_type: &TileType) -> bool {
+        Self::required_for_tile(tile_type).map_or(true, |technology| self.is_unlocked(technology))
+    }
+
+    /// The technology that unlocks an upgrade level, `None`
+    /// if it needs no research
+    pub fn required_for_upgrade(level: usize) -> Option<Technology> {
+        if level <= FREE_UPGRADE_LEVELS { return None }
+        Technology::ALL.into_iter()
+            .filter_map(|technology| technology.unlocks_upgrade_level().map(|max| (technology, max)))
+            .filter(|(_, max)| level <= *max)
+            .min_by_key(|(_, max)| *max)
+            .map(|(technology, _)| technology)
+    }
+
+    pub fn is_upgrade_unlocked(&self, level: usize) -> bool {
+        if level <= FREE_UPGRADE_LEVELS { return true }
+        Self::required_for_upgrade(level).is_some_and(|technology| self.is_unlocked(technology))
+    }
+
+    /// What is being researched, with the points put into it so far
+    pub fn current(&self) -> Option<(Technology, f32)> {
+        self.current.map(|technology| (technology, self.progress))
+    }
+
+    /// Researches the next available technology instead,
+    /// the progress of the current one is lost
+    pub fn select_next(&mut self) -> () {
+        let available = self.available();
+        if available.is_empty() { return }
+
+        let index = self.current
+            .and_then(|current| available.iter().position(|technology| *technology == current))
+            .map(|index| (index + 1) % available.len())
+            .unwrap_or(0);
+        self.current = Some(available[index]);
+        self.progress = 0.0;
+        self.dirty = true;
+    }
+
+    /// Puts research points into the current technology, returns
+    /// the technology if it was completed
+    pub fn add_points(&mut self, points: f32) -> Option<Technology> {
+        if self.current.is_none() { self.current = self.available().first().cloned(); }
+        let technology = self.current?;
+
+        self.progress += points;
+        self.dirty = true;
+        if self.progress < technology.cost() { return None }
+
+        self.unlocked.insert(technology);
+        self.progress = 0.0;
+        self.current = self.available().first().cloned();
+        Some(technology)
+    }
+
+    /// Research belongs to the world it was made on,
+    /// a save for another seed starts from nothing
+    fn load(save: &SaveFile, seed: u32) -> Self {
+        if save.get_parsed::<u32>("research.seed") != Some(seed) { return Self::default() }
+        let unlocked = save.get("research.unlocked").unwrap_or_default()
+            .split(',')
+            .filter_map(Technology::from_key)
+            .collect();
+        let current = save.get("research.current").and_then(Technology::from_key);
+        let progress = save.get_parsed("research.progress").unwrap_or(0.0);
+
+        Self { unlocked, current, progress, dirty: false }
+    }
+
+    fn store(&self, save: &mut SaveFile, seed: u32) -> () {
+        save.set("research.seed", seed);
+        let unlocked: Vec<&str> = Technology::ALL.iter()
+            .filter(|technology| self.is_unlocked(**technology))
+            .map(|technology| technology.key())
+            .collect();
+        save.set("research.unlocked", unlocked.join(","));
+        save.set("research.current", self.current.map(|technology| technology.key()).unwrap_or_default());
+        save.set("research.progress", self.progress);
+    }
+
+    fn setup(mut planet_q: Query<&mut Planet, With<PlayerPlanet>>) -> () {
+        let Ok(mut planet) = planet_q.get_single_mut() else { return };
+        planet.research = Self::load(&SaveFile::load(SAVE_PATH), planet.seed);
+        logger::log::cyan("research", format!("Loaded {} researched technologies", planet.research.unlocked.len()));
+    }
+
+    /// Tells the player about finished research, and writes the
+    /// research to the save file. Finished or picked research is
+    /// written right away, progress only now and then.
+    fn save(
+        mut commands: Commands,
+        mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+        mut researched: Local<Option<HashSet<Technology>>>,
+        mut selected: Local<Option<Technology>>,
+        mut ticks: Local<u32>,
+    ) -> () {
+        let Ok(mut planet) = planet_q.get_single_mut() else { return };
+
+        let known = researched.get_or_insert_with(|| planet.research.unlocked.clone());
+        let mut finished_any = false;
+        for technology in planet.research.unlocked.difference(known) {
+            finished_any = true;
+            logger::log::bright_green("research", format!("Researched {}", technology.display_name()));
+            commands.queue(SpawnInfoText(format!("Researched {}", technology.display_name())));
+        }
+        *known = planet.research.unlocked.clone();
+
+        let reselected = *selected != planet.research.current;
+        *selected = planet.research.current;
+
+        *ticks = ticks.wrapping_add(1);
+        if !planet.research.dirty || !(finished_any || reselected || *ticks % SAVE_INTERVAL_TICKS == 0) { return }
+        let mut save = SaveFile::load(SAVE_PATH);
+        let seed = planet.seed;
+        planet.research.store(&mut save, seed);
+        match save.write() {
+            Ok(_) => planet.research.dirty = false,
+            Err(e) => logger::log::bright_red("research", e),
+        }
+    }
+}
+
+pub struct ResearchPlugin;
+impl Plugin for ResearchPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::Loading), Research::setup.after(Planet::setup))
+            .add_systems(FixedUpdate, Research::save.in_set(GameTickSet));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_for_upgrade() {
+        assert_eq!(Research::required_for_upgrade(0), None);
+        assert_eq!(Research::required_for_upgrade(FREE_UPGRADE_LEVELS), None);
+        assert_eq!(Research::required_for_upgrade(2), Some(Technology::Refinement));
+        assert_eq!(Research::required_for_upgrade(3), Some(Technology::Engineering));
+        assert_eq!(Research::required_for_upgrade(100), Some(Technology::Engineering));
+
+        let mut research = Research::default();
+        assert!(research.is_upgrade_unlocked(1));
+        assert!(!research.is_upgrade_unlocked(2));
+        research.unlocked.insert(Technology::Refinement);
+        assert!(research.is_upgrade_unlocked(2));
+        assert!(!research.is_upgrade_unlocked(3));
+    }
+
+    #[test]
+    fn available_needs_requirements() {
+        let mut research = Research::default();
+        assert_eq!(research.available(), vec![Technology::Storage, Technology::WindPower, Technology::Mining]);
+
+        research.unlocked.insert(Technology::Storage);
+        assert_eq!(research.available(), vec![Technology::WindPower, Technology::Mining, Technology::Automation]);
+    }
+
+    #[test]
+    fn select_next_wraps_around() {
+        let mut research = Research::default();
+        research.select_next();
+        assert_eq!(research.current(), Some((Technology::Storage, 0.0)));
+
+        research.progress = 5.0;
+        research.select_next();
+        assert_eq!(research.current(), Some((Technology::WindPower, 0.0)));
+        research.select_next();
+        research.select_next();
+        assert_eq!(research.current(), Some((Technology::Storage, 0.0)));
+    }
+
+    #[test]
+    fn add_points_completes_research() {
+        let mut research = Research::default();
+        assert_eq!(research.add_points(15.0), None);
+        assert_eq!(research.current(), Some((Technology::Storage, 15.0)));
+
+        assert_eq!(research.add_points(10.0), Some(Technology::Storage));
+        assert!(research.is_unlocked(Technology::Storage));
+        assert!(research.is_tile_unlocked(&TileType::Battery(Battery)));
+        assert_eq!(research.current(), Some((Technology::WindPower, 0.0)));
+    }
+}
@@ -2,7 +2,7 @@ use bevy::utils::HashMap;
 
 /// The resources that can be found on a planet
 /// 
-/// * Important: Don't forget to update the `RESOURCE_TYPES` constant
+/// * Important: Don't forget to add new variants to `PlanetResource::ALL`
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 #[repr(u8)]
 pub enum PlanetResource {
@@ -11,10 +11,29 @@ pub enum PlanetResource {
     Copper,
 }
 
+impl PlanetResource {
+    /// Every resource, in the order they're shown in the HUD
+    pub const ALL: [PlanetResource; 3] = [Self::Wood, Self::Stone, Self::Copper];
+
+    /// Path to the icon shown next to the resource
+    pub fn icon(&self) -> &'static str {
+        match self {
+            Self::Wood => "icons/wood.png",
+            Self::Stone => "icons/stone.png",
+            Self::Copper => "icons/copper.png",
+        }
+    }
+}
+
 /// The resources that the player has
 #[derive(Debug, Clone)]
 pub struct PlanetResources {
     map: HashMap<PlanetResource, usize>,
+
+    /// How much of each resource has been added / removed
+    /// in total, used for statistics
+    produced: HashMap<PlanetResource, usize>,
+    consumed: HashMap<PlanetResource, usize>,
 }
 
 impl Default for PlanetResources {
@@ -25,6 +44,8 @@ impl Default for PlanetResources {
                 (PlanetResource::Stone, 150),
                 (PlanetResource::Copper, 100),
             ]),
+            produced: HashMap::new(),
+            consumed: HashMap::new(),
         }
     }
 }
@@ -37,11 +58,28 @@ impl PlanetResources {
     /// Adds a resource to the player
     pub fn add(&mut self, resource: PlanetResource, amount: usize) {
         *self.map.get_mut(&resource).unwrap() += amount;
+        *self.produced.entry(resource).or_insert(0) += amount;
     }
 
     /// Removes a resource from the player
     pub fn remove(&mut self, resource: PlanetResource, amount: usize) {
         *self.map.get_mut(&resource).unwrap() -= amount;
+        *self.consumed.entry(resource).or_insert(0) += amount;
+    }
+    /// Gives back something that was spent, it's taken off
+    /// what was consumed instead of counting as produced
+    pub fn refund(&mut self, resource: PlanetResource, amount: usize) {
+        *self.map.get_mut(&resource).unwrap() += amount;
+        let consumed = self.consumed.entry(resource).or_insert(0);
+        *consumed = consumed.saturating_sub(amount);
+    }
+    /// How much of a resource has been added since the start
+    pub fn total_produced(&self, resource: PlanetResource) -> usize {
+        self.produced.get(&resource).cloned().unwrap_or(0)
+    }
+    /// How much of a resource has been spent since the start
+    pub fn total_consumed(&self, resource: PlanetResource) -> usize {
+        self.consumed.get(&resource).cloned().unwrap_or(0)
     }
     pub fn has(&self, resource: PlanetResource, amount: usize) -> bool {
         self.get(resource) >= amount
@@ -63,4 +101,24 @@ impl PlanetResources {
         for (resource, cost) in resources { self.remove(*resource, *cost); }
         Ok(())
     }
+
+    /// Refunds `old` and spends `new` instead (e.g rerouting a cable),
+    /// only the difference between them has to be affordable
+    pub fn try_replace(&mut self, old: &[(PlanetResource, usize)], new: &[(PlanetResource, usize)]) -> Result<(), String> {
+        for (resource, cost) in new {
+            let refunded: usize = old.iter()
+                .filter(|(old_resource, _)| old_resource == resource)
+                .map(|(_, amount)| amount)
+                .sum();
+            let needed = cost.saturating_sub(refunded);
+            if !self.has(*resource, needed) {
+                let items_left = needed - self.get(*resource);
+                return Err(format!("Need {items_left}x more {resource:?}"))
+            }
+        }
+
+        for (resource, amount) in old { self.refund(*resource, *amount); }
+        for (resource, cost) in new { self.remove(*resource, *cost); }
+        Ok(())
+    }
 }
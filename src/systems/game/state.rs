@@ -0,0 +1,40 @@
+/* Imports */
+use bevy::prelude::*;
+
+/// Where in the app we are. The world is generated when
+/// entering `Loading`, and gameplay systems only run
+/// `in_state(AppState::InGame)`.
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum AppState {
+    #[default]
+    MainMenu,
+    /// Picking the seed and shape of the planet
+    NewGame,
+    Loading,
+    InGame,
+    Paused,
+}
+
+impl AppState {
+    /// Run condition for things that should keep going
+    /// (or be shown) while the game is paused
+    pub fn in_game_or_paused(state: Res<State<AppState>>) -> bool {
+        matches!(state.get(), Self::InGame | Self::Paused)
+    }
+
+    /// Everything that runs on `OnEnter(AppState::Loading)` has
+    /// been applied by now, so the game can start
+    fn finish_loading(mut next_state: ResMut<NextState<AppState>>) -> () {
+        next_state.set(AppState::InGame);
+    }
+}
+
+pub struct AppStatePlugin;
+impl Plugin for AppStatePlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_state::<AppState>()
+            .enable_state_scoped_entities::<AppState>()
+            .add_systems(Update, AppState::finish_loading.run_if(in_state(AppState::Loading)));
+    }
+}
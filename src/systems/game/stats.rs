@@ -0,0 +1,154 @@
+/* Imports */
+use bevy::{prelude::*, utils::HashMap};
+use crate::{components::planet::{Planet, PlayerPlanet}, utils::ring_buffer::RingBuffer};
+use super::{GameTickSet, PlanetResource, GAME_TICK_HZ};
+
+/* Constants */
+/// One hour of samples, one per tick
+const TICK_HISTORY_CAPACITY: usize = 60 * 60 * GAME_TICK_HZ as usize;
+/// How many ticks are merged into one session sample (one minute)
+const SESSION_SAMPLE_TICKS: usize = 60 * GAME_TICK_HZ as usize;
+/// One day of session samples
+const SESSION_HISTORY_CAPACITY: usize = 24 * 60;
+
+/// Something that is recorded every tick
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StatsSeries {
+    /// How much of a resource the player has
+    Total(PlanetResource),
+    /// How much of a resource was added
+    Produced(PlanetResource),
+    /// How much of a resource was spent
+    Consumed(PlanetResource),
+    /// Energy produced by all generators
+    Generation,
+    /// Energy wanted by all consumers
+    Demand,
+}
+
+impl StatsSeries {
+    /// Rates are summed when samples are merged,
+    /// everything else keeps the newest value.
+    pub fn is_rate(&self) -> bool {
+        !matches!(self, Self::Total(_))
+    }
+
+    pub fn display_name(&self) -> String {
+        match self {
+            Self::Total(resource) => format!("{resource:?}"),
+            Self::Produced(resource) => format!("+{resource:?}"),
+            Self::Consumed(resource) => format!("-{resource:?}"),
+            Self::Generation => "Generation".to_string(),
+            Self::Demand => "Demand".to_string(),
+        }
+    }
+}
+
+/// Values of every series over one or more ticks
+#[derive(Clone, Debug, Default)]
+pub struct StatsSample {
+    /// How many ticks this sample covers
+    pub ticks: usize,
+    pub values: HashMap<StatsSeries, f32>,
+}
+
+impl StatsSample {
+    /// The value per tick for rates, and the
+    /// value itself for everything else
+    pub fn get(&self, series: StatsSeries) -> f32 {
+        let value = self.values.get(&series).cloned().unwrap_or(0.0);
+        if series.is_rate() { value / self.ticks.max(1) as f32 } else { value }
+    }
+
+    /// Adds a newer sample to this one
+    fn merge(&mut self, other: &StatsSample) -> () {
+        self.ticks += other.ticks;
+        for (series, value) in &other.values {
+            let entry = self.values.entry(*series).or_insert(0.0);
+            if series.is_rate() { *entry += value } else { *entry = *value }
+        }
+    }
+
+    /// Merges many samples into one
+    pub fn merged<'a>(samples: impl Iterator<Item = &'a StatsSample>) -> StatsSample {
+        let mut merged = StatsSample::default();
+        for sample in samples { merged.merge(sample); }
+        merged
+    }
+}
+
+/// Recorded statistics of the players planet
+#[derive(Resource)]
+pub struct StatsHistory {
+    /// One sample per tick, for the last hour
+    pub ticks: RingBuffer<StatsSample>,
+
+    /// One sample per minute, for the whole session
+    pub session: RingBuffer<StatsSample>,
+
+    /// Ticks not yet merged into a session sample
+    pending: StatsSample,
+
+    /// Resource totals of the last tick, to calculate
+    /// how much was produced / consumed this tick
+    last_produced: HashMap<PlanetResource, usize>,
+    last_consumed: HashMap<PlanetResource, usize>,
+}
+
+impl Default for StatsHistory {
+    fn default() -> Self {
+        Self {
+            ticks: RingBuffer::new(TICK_HISTORY_CAPACITY),
+            session: RingBuffer::new(SESSION_HISTORY_CAPACITY),
+            pending: StatsSample::default(),
+            last_produced: HashMap::new(),
+            last_consumed: HashMap::new(),
+        }
+    }
+}
+
+impl StatsHistory {
+    /// Takes a sample of the planet every tick
+    fn record(mut history: ResMut<StatsHistory>, planet_q: Query<&Planet, With<PlayerPlanet>>) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        let mut sample = StatsSample { ticks: 1, values: HashMap::new() };
+
+        for resource in PlanetResource::ALL {
+            let produced = planet.resources.total_produced(resource);
+            let consumed = planet.resources.total_consumed(resource);
+            let last_produced = history.last_produced.insert(resource, produced).unwrap_or(produced);
+            let last_consumed = history.last_consumed.insert(resource, consumed).unwrap_or(consumed);
+
+            sample.values.insert(StatsSeries::Total(resource), planet.resources.get(resource) as f32);
+            sample.values.insert(StatsSeries::Produced(resource), produced.saturating_sub(last_produced) as f32);
+            sample.values.insert(StatsSeries::Consumed(resource), consumed.saturating_sub(last_consumed) as f32);
+        }
+
+        sample.values.insert(StatsSeries::Generation, planet.tiles.values()
+            .filter_map(|tile| tile.energy_output()).sum());
+        sample.values.insert(StatsSeries::Demand, planet.tiles.values()
+            .map(|tile| tile.powergrid_status.energy_demanded).sum());
+
+        history.pending.merge(&sample);
+        if history.pending.ticks >= SESSION_SAMPLE_TICKS {
+            let pending = std::mem::take(&mut history.pending);
+            history.session.push(pending);
+        }
+        history.ticks.push(sample);
+    }
+
+    /// Session samples including the ticks that haven't
+    /// been merged into one yet
+    pub fn session_samples(&self) -> impl Iterator<Item = &StatsSample> {
+        self.session.iter().chain(Some(&self.pending).filter(|pending| pending.ticks > 0))
+    }
+}
+
+pub struct StatsHistoryPlugin;
+impl Plugin for StatsHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<StatsHistory>()
+            .add_systems(FixedUpdate, StatsHistory::record.in_set(GameTickSet));
+    }
+}
@@ -1,13 +1,93 @@
 /* Imports */
 use bevy::prelude::*;
+use super::AppState;
 
 /* Constants */
 pub const GAME_TICK_HZ: f64 = 2.0;
+/// Speeds that can be picked, as multipliers of `GAME_TICK_HZ`
+const SPEEDS: [u32; 3] = [1, 2, 4];
+
+/// Systems that advance the game every fixed tick. They only run
+/// in game, and not while the game speed is paused.
+#[derive(SystemSet, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GameTickSet;
+
+/// How fast the fixed tick runs. Changing it scales
+/// `Time<Fixed>`, so `Update` stays smooth.
+#[derive(Resource, Default)]
+pub struct GameSpeed {
+    /// Index into `SPEEDS`
+    speed: usize,
+    paused: bool,
+
+    /// If a single tick should run while paused
+    step: bool,
+}
+
+impl GameSpeed {
+    pub fn multiplier(&self) -> u32 {
+        SPEEDS[self.speed]
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    /// For things in `Update` that should follow the
+    /// game speed, 0 while paused
+    pub fn time_scale(&self) -> f32 {
+        if self.paused { 0.0 } else { self.multiplier() as f32 }
+    }
+
+    pub fn display_name(&self) -> String {
+        if self.paused { "Paused".to_string() } else { format!("{}x", self.multiplier()) }
+    }
+
+    /// Run condition for `GameTickSet`
+    fn ticking(speed: Res<GameSpeed>) -> bool {
+        !speed.paused || speed.step
+    }
+
+    /// [ slower, ] faster, \ pause, . single tick while paused
+    fn controls(
+        kb: Res<ButtonInput<KeyCode>>,
+        mut speed: ResMut<GameSpeed>,
+        mut fixed: ResMut<Time<Fixed>>,
+    ) -> () {
+        if kb.just_pressed(KeyCode::BracketLeft) {
+            speed.speed = speed.speed.saturating_sub(1);
+        }
+        if kb.just_pressed(KeyCode::BracketRight) {
+            speed.speed = (speed.speed + 1).min(SPEEDS.len() - 1);
+        }
+        if kb.just_pressed(KeyCode::Backslash) {
+            speed.paused = !speed.paused;
+        }
+        if kb.just_pressed(KeyCode::Period) && speed.paused {
+            speed.step = true;
+        }
+
+        if speed.is_changed() {
+            fixed.set_timestep_hz(GAME_TICK_HZ * speed.multiplier() as f64);
+        }
+    }
+
+    /// A step is only one tick
+    fn finish_step(mut speed: ResMut<GameSpeed>) -> () {
+        if speed.step { speed.step = false; }
+    }
+}
 
 pub struct GameTickPlugin;
 impl Plugin for GameTickPlugin {
     fn build(&self, app: &mut App) {
         app
-            .insert_resource(Time::<Fixed>::from_hz(GAME_TICK_HZ));
+            .insert_resource(Time::<Fixed>::from_hz(GAME_TICK_HZ))
+            .init_resource::<GameSpeed>()
+            .configure_sets(FixedUpdate, GameTickSet
+                .run_if(in_state(AppState::InGame))
+                .run_if(GameSpeed::ticking))
+            .add_systems(FixedUpdate, GameSpeed::finish_step.after(GameTickSet))
+            .add_systems(Update, GameSpeed::controls.run_if(in_state(AppState::InGame)));
     }
 }
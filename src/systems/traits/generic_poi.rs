@@ -11,4 +11,11 @@ pub trait GenericPointOfInterest {
         asset_server: &Res<AssetServer>,
         transform: Transform,
     ) -> Entity;
+
+    /// How many tile indices this POI covers
+    fn width(&self) -> usize { 1 }
+
+    /// How tall this POI is for the player, `None`
+    /// if the player walks through it
+    fn collision_height(&self) -> Option<f32> { None }
 }
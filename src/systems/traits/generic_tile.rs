@@ -2,7 +2,7 @@
 use bevy::prelude::*;
 use crate::{
     components::{planet::Planet, poi::PointOfInterestType},
-    systems::game::PlanetResource,
+    systems::{game::PlanetResource, traits::TileUpgrade},
     tile::{spawn::{TileSpawnEvent, TileSpawnEventParams}, Tile, TileType},
     utils::audio::{PlayAudioEvent, game_sounds},
 };
@@ -34,6 +34,14 @@ pub trait GenericTile {
     /// solar panels, wind turbines, etc. (Energy per gametick)
     fn energy_output(&self, tile: &Tile) -> Option<f32> { None }
 
+    /// Tiles that need energy to work should implement this,
+    /// like drills. (Energy per gametick)
+    fn energy_consumption(&self, tile: &Tile) -> Option<f32> { None }
+
+    /// Tiles that mine resources should implement this, like
+    /// drills. (Resources per gametick, can be fractions)
+    fn expected_yield(&self, tile: &Tile, planet: &Planet) -> Vec<(PlanetResource, f32)> { Vec::new() }
+
     /// How much energy this tile can store, as a maximum
     fn energy_capacity(&self, tile: &Tile) -> f32 { 50.0 }
 
@@ -44,6 +52,11 @@ pub trait GenericTile {
     /// How many tile slots this takes up
     fn width(&self) -> usize { 1 }
 
+    /// How tall this tile is for the player, `None` if the
+    /// player walks through it. The footprint is as wide
+    /// as the tile, see [`Self::width`].
+    fn collision_height(&self) -> Option<f32> { None }
+
     /// What POI:s this tile interacts with
     fn interacts_with(&self) -> Vec<PointOfInterestType> { Vec::new() }
 
@@ -52,15 +65,31 @@ pub trait GenericTile {
 
     /// What upgrades this tile has.
     /// 
-    /// The outer vector is the upgrade level, the inner vector
-    /// is the cost of the upgrade. So `.upgrades()[0]` is the cost
-    /// of upgrading to level 1. The cost of actually building the
-    /// tile is not included in this vector, it can be found in [`Self::cost`]
-    fn upgrades(&self) -> Vec<Vec<(PlanetResource, usize)>> { Vec::new() }
+    /// Every item is one upgrade level, so `.upgrades()[0]` is
+    /// the upgrade to level 1. Each level has a cost and the stat
+    /// modifiers it applies, see [`Tile::stat`]. The cost of
+    /// actually building the tile is not included in this vector,
+    /// it can be found in [`Self::cost`]
+    fn upgrades(&self) -> Vec<TileUpgrade> { Vec::new() }
+
+    /// How much energy can pass through this tile every tick
+    /// before it stops conducting, like breakers.
+    fn max_throughput(&self) -> Option<f32> { None }
 
-    /// Tiles that can't be removed by the player, like the rocketship
+    /// Tiles that can be toggled from the stats menu, like
+    /// switches. Returns the text of the toggle button.
+    fn toggle_label(&self, tile: &Tile) -> Option<String> { None }
+
+    /// What happens when the toggle button is pressed
+    fn on_toggle(&self, tile_id: usize, planet: &mut Planet) -> () {}
+
+    /// Tiles that can't be removed by the player, like the rocketship.
+    /// These don't take damage either.
     fn indestructible(&self) -> bool { false }
 
+    /// How much damage the tile can take before it's destroyed
+    fn max_health(&self) -> f32 { 100.0 }
+
     /// What tiles this tile needs to "keep distance" from
     /// to avoid collisions looking ugly. Like wind turbines
     /// which would cause the rotors to overlap.
@@ -8,3 +8,6 @@ pub use generic_poi::*;
 
 mod powergrid_status;
 pub use powergrid_status::*;
+
+mod tile_upgrade;
+pub use tile_upgrade::*;
@@ -8,6 +8,31 @@ pub struct PowergridStatus {
 
     // Energy that is stored in this tile (won't move)
     pub energy_stored: f32,
+
+    /// In what order this tile gets energy when the
+    /// network doesn't produce enough for everyone.
+    pub priority: PowerPriority,
+
+    /// If this tile had enough energy stored to run
+    /// during the last tick. Tiles that don't consume
+    /// energy are always considered powered.
+    pub powered: bool,
+
+    /// If energy can pass through this tile to the tiles
+    /// connected to it. Switches and breakers turn this off
+    /// to cut the grid in two.
+    pub conducting: bool,
+
+    /// How much energy this tile recieved during the last tick
+    pub energy_received: f32,
+
+    /// How much energy this tile wanted during the last tick,
+    /// its consumption or less if it's almost full.
+    pub energy_demanded: f32,
+
+    /// If more energy than this tile (or one of its cables)
+    /// can handle tried to pass through it during the last tick
+    pub overloaded: bool,
 }
 
 impl Default for PowergridStatus {
@@ -15,6 +40,44 @@ impl Default for PowergridStatus {
         Self {
             connected_tiles: Vec::new(),
             energy_stored: 0.0,
+            priority: PowerPriority::default(),
+            powered: true,
+            conducting: true,
+            energy_received: 0.0,
+            energy_demanded: 0.0,
+            overloaded: false,
+        }
+    }
+}
+
+/// Tiles with a higher priority get their energy
+/// first, the rest is shared among the lower ones.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PowerPriority {
+    Critical,
+    #[default]
+    Normal,
+    Low,
+}
+
+impl PowerPriority {
+    /// All priorities, highest first
+    pub const ALL: [PowerPriority; 3] = [Self::Critical, Self::Normal, Self::Low];
+
+    /// Used to cycle through the priorities in the UI
+    pub fn next(&self) -> Self {
+        match self {
+            Self::Critical => Self::Normal,
+            Self::Normal => Self::Low,
+            Self::Low => Self::Critical,
+        }
+    }
+
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::Critical => "Critical",
+            Self::Normal => "Normal",
+            Self::Low => "Low",
         }
     }
 }
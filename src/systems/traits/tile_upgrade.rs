@@ -0,0 +1,71 @@
+/* Imports */
+use crate::systems::game::PlanetResource;
+
+/// A stat of a tile that upgrades can change
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum TileStat {
+    /// Energy produced per tick
+    EnergyOutput,
+    /// How much energy can be stored
+    EnergyCapacity,
+    /// How many tiles left and right the tile reaches
+    InteractionRange,
+    /// How many tile slots the tile takes up
+    Width,
+    /// Multiplier for how much drills mine every tick
+    DrillSpeed,
+}
+
+impl TileStat {
+    pub fn display_name(&self) -> &'static str {
+        match self {
+            Self::EnergyOutput => "Output",
+            Self::EnergyCapacity => "Capacity",
+            Self::InteractionRange => "Range",
+            Self::Width => "Width",
+            Self::DrillSpeed => "Drill speed",
+        }
+    }
+}
+
+/// How an upgrade changes a stat. Modifiers are applied
+/// in the order of the upgrade levels.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum StatModifier {
+    Add(TileStat, f32),
+    Multiply(TileStat, f32),
+}
+
+impl StatModifier {
+    pub fn stat(&self) -> TileStat {
+        match self {
+            Self::Add(stat, _) | Self::Multiply(stat, _) => *stat,
+        }
+    }
+
+    pub fn apply(&self, value: f32) -> f32 {
+        match self {
+            Self::Add(_, amount) => value + amount,
+            Self::Multiply(_, factor) => value * factor,
+        }
+    }
+}
+
+/// One level of upgrades, what it costs and what it changes
+#[derive(Clone, Debug)]
+pub struct TileUpgrade {
+    pub cost: Vec<(PlanetResource, usize)>,
+    pub modifiers: Vec<StatModifier>,
+}
+
+impl TileUpgrade {
+    pub fn new(cost: Vec<(PlanetResource, usize)>) -> Self {
+        Self { cost, modifiers: Vec::new() }
+    }
+
+    /// Adds a stat modifier to this upgrade
+    pub fn with(mut self, modifier: StatModifier) -> Self {
+        self.modifiers.push(modifier);
+        self
+    }
+}
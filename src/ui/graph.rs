@@ -0,0 +1,258 @@
+/* Imports */
+use bevy::prelude::*;
+use crate::{camera::UI_LAYERS, systems::game::{PlanetResource, StatsHistory, StatsSample, StatsSeries, GAME_TICK_HZ}, utils::color::hex};
+
+/* Constants */
+const GRAPH_WIDTH: f32 = 320.0;
+const GRAPH_HEIGHT: f32 = 120.0;
+/// How many points every series is drawn with
+const GRAPH_COLUMNS: usize = 80;
+const DOT_SIZE: f32 = 3.0;
+const SERIES_COLORS: [&str; 3] = ["#3ad13a", "#e8c21a", "#1ab0ff"];
+
+/// Which part of the history is shown
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphWindow {
+    Minute,
+    Hour,
+    Session,
+}
+
+/// What is shown in the graph
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GraphCategory {
+    Resources,
+    Produced,
+    Consumed,
+    Power,
+}
+
+impl GraphCategory {
+    fn series(&self) -> Vec<StatsSeries> {
+        let resources = PlanetResource::ALL;
+        match self {
+            Self::Resources => resources.map(StatsSeries::Total).to_vec(),
+            Self::Produced => resources.map(StatsSeries::Produced).to_vec(),
+            Self::Consumed => resources.map(StatsSeries::Consumed).to_vec(),
+            Self::Power => vec![StatsSeries::Generation, StatsSeries::Demand],
+        }
+    }
+}
+
+#[derive(Resource)]
+struct GraphState {
+    open: bool,
+    window: GraphWindow,
+    category: GraphCategory,
+}
+
+impl Default for GraphState {
+    fn default() -> Self {
+        Self { open: false, window: GraphWindow::Minute, category: GraphCategory::Resources }
+    }
+}
+
+#[derive(Component)]
+struct GraphUI;
+
+/// A point in the graph
+#[derive(Component)]
+struct GraphDot {
+    series: usize,
+    column: usize,
+}
+
+/// Name and latest value of a series
+#[derive(Component)]
+struct GraphLegend(usize);
+
+pub struct GraphPlugin;
+impl Plugin for GraphPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<GraphState>()
+            .add_systems(Startup, setup)
+            .add_systems(Update, (toggle, update).chain());
+    }
+}
+
+fn setup(mut commands: Commands) {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Vh(7.0),
+            right: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            padding: UiRect::all(Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(hex!("#503010")),
+        GraphUI,
+        UI_LAYERS,
+        Visibility::Hidden,
+    ))
+    .with_children(|parent| {
+        /* Time window buttons */
+        parent.spawn(Node { column_gap: Val::Px(4.0), ..default() })
+            .with_children(|parent| {
+                for (window, label) in [
+                    (GraphWindow::Minute, "Minute"),
+                    (GraphWindow::Hour, "Hour"),
+                    (GraphWindow::Session, "Session"),
+                ] {
+                    spawn_button(parent, label).observe(
+                        move |_: Trigger<Pointer<Down>>, mut state: ResMut<GraphState>| {
+                        state.window = window;
+                    });
+                }
+            });
+
+        /* Category buttons */
+        parent.spawn(Node { column_gap: Val::Px(4.0), ..default() })
+            .with_children(|parent| {
+                for (category, label) in [
+                    (GraphCategory::Resources, "Resources"),
+                    (GraphCategory::Produced, "Produced"),
+                    (GraphCategory::Consumed, "Consumed"),
+                    (GraphCategory::Power, "Power"),
+                ] {
+                    spawn_button(parent, label).observe(
+                        move |_: Trigger<Pointer<Down>>, mut state: ResMut<GraphState>| {
+                        state.category = category;
+                    });
+                }
+            });
+
+        /* Legend */
+        parent.spawn(Node { column_gap: Val::Px(10.0), ..default() })
+            .with_children(|parent| {
+                for (i, color) in SERIES_COLORS.iter().enumerate() {
+                    parent.spawn((
+                        Text::new(""),
+                        TextFont {
+                            font_size: 10.0,
+                            ..default()
+                        },
+                        TextColor(hex!(color)),
+                        GraphLegend(i),
+                    ));
+                }
+            });
+
+        /* Graph */
+        parent.spawn((
+            Node {
+                width: Val::Px(GRAPH_WIDTH),
+                height: Val::Px(GRAPH_HEIGHT),
+                ..default()
+            },
+            BackgroundColor(hex!("#2a1808")),
+        ))
+        .with_children(|parent| {
+            for (series, color) in SERIES_COLORS.iter().enumerate() {
+                for column in 0..GRAPH_COLUMNS {
+                    parent.spawn((
+                        Node {
+                            position_type: PositionType::Absolute,
+                            width: Val::Px(DOT_SIZE),
+                            height: Val::Px(DOT_SIZE),
+                            ..default()
+                        },
+                        BackgroundColor(hex!(color)),
+                        GraphDot { series, column },
+                    ));
+                }
+            }
+        });
+    });
+}
+
+fn spawn_button<'a>(parent: &'a mut ChildBuilder, label: &str) -> EntityCommands<'a> {
+    let mut button = parent.spawn((
+        Button,
+        Node {
+            padding: UiRect::axes(Val::Px(6.0), Val::Px(3.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            ..default()
+        },
+        BorderColor(Color::BLACK),
+    ));
+    button.with_child((
+        Text::new(label),
+        TextFont {
+            font_size: 10.0,
+            ..default()
+        },
+    ));
+    button
+}
+
+/// F2
+fn toggle(
+    kb: Res<ButtonInput<KeyCode>>,
+    mut state: ResMut<GraphState>,
+    mut ui_q: Query<&mut Visibility, With<GraphUI>>,
+) -> () {
+    if !kb.just_pressed(KeyCode::F2) { return }
+    state.open = !state.open;
+    for mut visibility in ui_q.iter_mut() {
+        *visibility = if state.open { Visibility::Visible } else { Visibility::Hidden };
+    }
+}
+
+/// Redraws the graph when new samples are recorded
+/// or another window / category is selected
+fn update(
+    state: Res<GraphState>,
+    history: Res<StatsHistory>,
+    mut dot_q: Query<(&GraphDot, &mut Node)>,
+    mut legend_q: Query<(&GraphLegend, &mut Text)>,
+) -> () {
+    if !state.open || !(state.is_changed() || history.is_changed()) { return }
+
+    let samples: Vec<&StatsSample> = match state.window {
+        GraphWindow::Minute => history.ticks.last_n(60 * GAME_TICK_HZ as usize).collect(),
+        GraphWindow::Hour => history.ticks.iter().collect(),
+        GraphWindow::Session => history.session_samples().collect(),
+    };
+
+    // Merge samples so that they fit in the graph, the
+    // newest column is always to the right
+    let chunk_size = samples.len().div_ceil(GRAPH_COLUMNS).max(1);
+    let columns: Vec<StatsSample> = samples
+        .rchunks(chunk_size)
+        .rev()
+        .map(|chunk| StatsSample::merged(chunk.iter().cloned()))
+        .collect();
+    let offset = GRAPH_COLUMNS.saturating_sub(columns.len());
+
+    let series = state.category.series();
+    let max = columns.iter()
+        .flat_map(|column| series.iter().map(|s| column.get(*s)))
+        .fold(1.0, f32::max);
+
+    for (dot, mut node) in dot_q.iter_mut() {
+        let value = series.get(dot.series).and_then(|s| {
+            let column = columns.get(dot.column.checked_sub(offset)?)?;
+            Some(column.get(*s))
+        });
+
+        match value {
+            Some(value) => {
+                node.display = Display::Flex;
+                node.left = Val::Px(dot.column as f32 * GRAPH_WIDTH / GRAPH_COLUMNS as f32);
+                node.bottom = Val::Px(value / max * (GRAPH_HEIGHT - DOT_SIZE));
+            },
+            None => node.display = Display::None,
+        }
+    }
+
+    for (legend, mut text) in legend_q.iter_mut() {
+        text.0 = match series.get(legend.0) {
+            Some(s) => format!("{}: {:.1}", s.display_name(),
+                columns.last().map_or(0.0, |column| column.get(*s))),
+            None => String::new(),
+        };
+    }
+}
@@ -1,17 +1,71 @@
 /* Imports */
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    camera::UI_LAYERS,
+    components::{planet::{Planet, PlayerPlanet}, player::{health::{PlayerHealth, PLAYER_MAX_HEALTH}, player::Player, tool::{ToolBelt, PLAYER_MAX_ENERGY}}},
+    systems::game::{GameSpeed, PlanetResource, StatsHistory, StatsSample, StatsSeries, GAME_TICK_HZ},
+    utils::color::hex,
+};
+
+/* Constants */
+const ICON_SIZE: f32 = 16.0;
+const ARROW_SIZE: f32 = 7.0;
+/// How many seconds of history the rate arrows are based on
+const RATE_WINDOW_SECONDS: usize = 60;
+/// How long a floating +N / -N text lives (seconds)
+const DELTA_LIFETIME: f32 = 1.2;
+/// How far a floating text drifts during its lifetime
+const DELTA_DISTANCE: f32 = 18.0;
+const GAIN_COLOR: &str = "#3ad13a";
+const LOSS_COLOR: &str = "#db1a1a";
+const HEALTH_BAR_WIDTH: f32 = 80.0;
+const HEALTH_BAR_HEIGHT: f32 = 8.0;
+/// Below this fraction of max health the bar turns red
+const LOW_HEALTH: f32 = 0.3;
 
-use crate::{camera::UI_LAYERS, components::planet::{Planet, PlayerPlanet}, systems::game::{GameState, PlanetResource}};
 pub struct HudPlugin;
 impl Plugin for HudPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, setup)
-            .add_systems(Update, update);
+            .add_systems(Update, (update, update_rates, animate_deltas, update_player_status, update_health_bar, update_game_speed));
     }
 }
 
+/// Holds the icon, amount and rate of a resource
+#[derive(Component)]
+struct ResourceEntry(PlanetResource);
+
+#[derive(Component)]
+struct ResourceAmount(PlanetResource);
+
+/// Arrow and text showing how much of a resource
+/// was gained / lost during the last minute
+#[derive(Component)]
+struct ResourceRateArrow(PlanetResource);
+#[derive(Component)]
+struct ResourceRateText(PlanetResource);
+
+/// Selected tool and portable energy of the player
+#[derive(Component)]
+struct PlayerStatusText;
+
+/// Current game speed, see [`GameSpeed`]
+#[derive(Component)]
+struct GameSpeedText;
+
+/// The filled part of the health bar
+#[derive(Component)]
+struct HealthBarFill;
+
+/// A +N / -N text floating away from a resource
+#[derive(Component)]
+struct FloatingDelta {
+    age: f32,
+}
+
 /* Systems */
-fn setup(mut commands: Commands, _asset_server: Res<AssetServer>) {
+fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.spawn((
         Node {
             width: Val::Percent(100.0),
@@ -37,39 +91,246 @@ fn setup(mut commands: Commands, _asset_server: Res<AssetServer>) {
                 Node {
                     width: Val::Percent(100.),
                     flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Center,
                     padding: UiRect::all(Val::Px(5.)).with_left(Val::Px(120.0)),
-                    row_gap: Val::Px(5.),
+                    column_gap: Val::Px(18.),
 
                     ..default()
                 },
                 BackgroundColor(Color::srgb(0.15, 0.15, 0.15)),
             ))
             .with_children(|parent| {
-                // text
+                for resource in PlanetResource::ALL {
+                    spawn_entry(parent, &asset_server, resource);
+                }
+
                 parent.spawn((
-                    Text::new("Wood: 0, Stone: 0, Copper: 0"),
+                    Text::new(""),
                     TextFont {
                         font_size: 12.0,
                         ..default()
                     },
-                    Label,
+                    GameSpeedText,
+                ));
+
+                parent.spawn((
+                    Text::new(""),
+                    TextFont {
+                        font_size: 12.0,
+                        ..default()
+                    },
+                    Node {
+                        margin: UiRect::left(Val::Auto),
+                        ..default()
+                    },
+                    PlayerStatusText,
+                ));
+
+                parent.spawn((
+                    Node {
+                        width: Val::Px(HEALTH_BAR_WIDTH),
+                        height: Val::Px(HEALTH_BAR_HEIGHT),
+                        margin: UiRect::right(Val::Px(120.0)),
+                        border: UiRect::all(Val::Px(1.0)),
+                        ..default()
+                    },
+                    BorderColor(Color::BLACK),
+                    BackgroundColor(hex!("#3a0a0a")),
+                ))
+                .with_child((
+                    Node {
+                        width: Val::Percent(100.0),
+                        height: Val::Percent(100.0),
+                        ..default()
+                    },
+                    BackgroundColor(hex!(GAIN_COLOR)),
+                    HealthBarFill,
                 ));
             });
         });
     });
 }
 
+fn spawn_entry(parent: &mut ChildBuilder, asset_server: &AssetServer, resource: PlanetResource) -> () {
+    parent.spawn((
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(4.),
+            ..default()
+        },
+        ResourceEntry(resource),
+    ))
+    .with_children(|parent| {
+        parent.spawn((
+            ImageNode::new(asset_server.load(resource.icon())),
+            Node {
+                width: Val::Px(ICON_SIZE),
+                height: Val::Px(ICON_SIZE),
+                ..default()
+            },
+        ));
+        parent.spawn((
+            Text::new("0"),
+            TextFont {
+                font_size: 12.0,
+                ..default()
+            },
+            ResourceAmount(resource),
+        ));
+        parent.spawn((
+            ImageNode::new(asset_server.load("icons/arrow.png")),
+            Node {
+                width: Val::Px(ARROW_SIZE),
+                height: Val::Px(ARROW_SIZE),
+                ..default()
+            },
+            Visibility::Hidden,
+            ResourceRateArrow(resource),
+        ));
+        parent.spawn((
+            Text::new(""),
+            TextFont {
+                font_size: 10.0,
+                ..default()
+            },
+            ResourceRateText(resource),
+        ));
+    });
+}
+
+/// Rebuilds the amounts when the resources change, and
+/// spawns a floating text for every change
 fn update(
-    planet_q: Query<&Planet, With<PlayerPlanet>>,
-    mut query: Query<&mut Text, With<Label>>,
-) {
-    // TODO: Something like Resouce.is_changed()
-    let planet = planet_q.single();
-    for mut text in &mut query {
-        text.0 = format!("Wood: {}, Stone: {}, Copper: {}",
-            planet.resources.get(PlanetResource::Wood),
-            planet.resources.get(PlanetResource::Stone),
-            planet.resources.get(PlanetResource::Copper),
-        );
+    mut commands: Commands,
+    planet_q: Query<Ref<Planet>, With<PlayerPlanet>>,
+    mut last_amounts: Local<HashMap<PlanetResource, usize>>,
+    mut amount_q: Query<(&ResourceAmount, &mut Text)>,
+    entry_q: Query<(Entity, &ResourceEntry)>,
+) -> () {
+    let Ok(planet) = planet_q.get_single() else { return };
+    if !planet.is_changed() { return }
+
+    for resource in PlanetResource::ALL {
+        let amount = planet.resources.get(resource);
+        let Some(last) = last_amounts.insert(resource, amount) else {
+            // First time we see the resource, no delta to show
+            set_amount(&mut amount_q, resource, amount);
+            continue;
+        };
+        if last == amount { continue }
+
+        set_amount(&mut amount_q, resource, amount);
+        let Some((entry, _)) = entry_q.iter().find(|(_, entry)| entry.0 == resource) else { continue };
+        let (label, color) = if amount > last { (format!("+{}", amount - last), GAIN_COLOR) }
+            else { (format!("-{}", last - amount), LOSS_COLOR) };
+
+        commands.entity(entry).with_child((
+            Node {
+                position_type: PositionType::Absolute,
+                left: Val::Px(ICON_SIZE + 4.0),
+                top: Val::Px(ICON_SIZE),
+                ..default()
+            },
+            Text::new(label),
+            TextFont {
+                font_size: 10.0,
+                ..default()
+            },
+            TextColor(hex!(color)),
+            FloatingDelta { age: 0.0 },
+            PickingBehavior::IGNORE,
+        ));
+    }
+}
+
+fn set_amount(amount_q: &mut Query<(&ResourceAmount, &mut Text)>, resource: PlanetResource, amount: usize) -> () {
+    for (_, mut text) in amount_q.iter_mut().filter(|(a, _)| a.0 == resource) {
+        text.0 = amount.to_string();
+    }
+}
+
+/// Shows how much of every resource was gained /
+/// lost during the last minute
+fn update_rates(
+    history: Res<StatsHistory>,
+    mut arrow_q: Query<(&ResourceRateArrow, &mut ImageNode, &mut Visibility)>,
+    mut text_q: Query<(&ResourceRateText, &mut Text, &mut TextColor)>,
+) -> () {
+    if !history.is_changed() { return }
+    let ticks = RATE_WINDOW_SECONDS * GAME_TICK_HZ as usize;
+    let sample = StatsSample::merged(history.ticks.last_n(ticks));
+
+    // Per tick rate, scaled up to one minute
+    let rate = |resource: PlanetResource| (
+        sample.get(StatsSeries::Produced(resource)) - sample.get(StatsSeries::Consumed(resource))
+    ) * GAME_TICK_HZ as f32 * 60.0;
+
+    for (arrow, mut image, mut visibility) in arrow_q.iter_mut() {
+        let rate = rate(arrow.0).round();
+        *visibility = if rate == 0.0 { Visibility::Hidden } else { Visibility::Inherited };
+        image.flip_y = rate < 0.0;
+        image.color = if rate < 0.0 { hex!(LOSS_COLOR) } else { hex!(GAIN_COLOR) };
+    }
+
+    for (rate_text, mut text, mut color) in text_q.iter_mut() {
+        let rate = rate(rate_text.0).round();
+        text.0 = if rate == 0.0 { String::new() } else { format!("{}/min", rate.abs()) };
+        color.0 = if rate < 0.0 { hex!(LOSS_COLOR) } else { hex!(GAIN_COLOR) };
+    }
+}
+
+/// Floating texts drift downwards and fade out
+fn animate_deltas(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut delta_q: Query<(Entity, &mut FloatingDelta, &mut Node, &mut TextColor)>,
+) -> () {
+    for (entity, mut delta, mut node, mut color) in delta_q.iter_mut() {
+        delta.age += time.delta_secs();
+        if delta.age >= DELTA_LIFETIME {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let progress = delta.age / DELTA_LIFETIME;
+        node.top = Val::Px(ICON_SIZE + progress * DELTA_DISTANCE);
+        color.0.set_alpha(1.0 - progress);
+    }
+}
+
+fn update_player_status(
+    player_q: Query<(Ref<Player>, Ref<ToolBelt>)>,
+    mut text_q: Query<&mut Text, With<PlayerStatusText>>,
+) -> () {
+    let Ok((player, tool_belt)) = player_q.get_single() else { return };
+    if !player.is_changed() && !tool_belt.is_changed() { return }
+
+    for mut text in text_q.iter_mut() {
+        text.0 = format!("{} | Energy {:.0}/{:.0}",
+            tool_belt.selected.display_name(), player.energy, PLAYER_MAX_ENERGY);
+    }
+}
+
+fn update_game_speed(
+    speed: Res<GameSpeed>,
+    mut text_q: Query<&mut Text, With<GameSpeedText>>,
+) -> () {
+    if !speed.is_changed() { return }
+    for mut text in text_q.iter_mut() {
+        text.0 = format!("Speed {}", speed.display_name());
+    }
+}
+
+fn update_health_bar(
+    health_q: Query<&PlayerHealth, Changed<PlayerHealth>>,
+    mut fill_q: Query<(&mut Node, &mut BackgroundColor), With<HealthBarFill>>,
+) -> () {
+    let Ok(health) = health_q.get_single() else { return };
+    let fraction = (health.health / PLAYER_MAX_HEALTH).clamp(0.0, 1.0);
+
+    for (mut node, mut color) in fill_q.iter_mut() {
+        node.width = Val::Percent(fraction * 100.0);
+        color.0 = if fraction < LOW_HEALTH { hex!(LOSS_COLOR) } else { hex!(GAIN_COLOR) };
     }
 }
@@ -0,0 +1,143 @@
+/* Imports */
+use bevy::prelude::*;
+use crate::{
+    camera::UI_LAYERS,
+    components::{cable::{edit::CableSelection, slot_state::SlotCablePlacementResource}, tile::spawn::TilePreview},
+    systems::game::AppState,
+    ui::stats::InspectorState,
+    utils::color::hex,
+};
+
+/* Constants */
+const TITLE: &str = "game";
+pub const BACKGROUND_COLOR: &str = "#0b0b12";
+const PAUSE_BACKGROUND_COLOR: &str = "#000000aa";
+const BUTTON_COLOR: &str = "#503010";
+pub const BUTTON_WIDTH: f32 = 160.0;
+/// Drawn on top of the HUD and everything else
+const MENU_Z_INDEX: i32 = 100;
+
+pub struct MenuPlugin;
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(OnEnter(AppState::MainMenu), spawn_main_menu)
+            .add_systems(OnEnter(AppState::Loading), spawn_loading)
+            .add_systems(OnEnter(AppState::Paused), spawn_pause_menu)
+            .add_systems(PreUpdate, toggle_pause.run_if(AppState::in_game_or_paused));
+    }
+}
+
+/// A full screen node that is removed when leaving `state`
+pub fn spawn_screen<'a>(commands: &'a mut Commands, state: AppState, background: Color) -> EntityCommands<'a> {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            row_gap: Val::Px(8.0),
+            ..default()
+        },
+        BackgroundColor(background),
+        GlobalZIndex(MENU_Z_INDEX),
+        StateScoped(state),
+        UI_LAYERS,
+    ))
+}
+
+pub fn spawn_title(parent: &mut ChildBuilder, asset_server: &AssetServer, text: &str) -> () {
+    parent.spawn((
+        Text::new(text),
+        TextFont {
+            font: asset_server.load("fonts/ByteBounce.ttf"),
+            font_size: 48.0,
+            ..default()
+        },
+        Node { margin: UiRect::bottom(Val::Px(16.0)), ..default() },
+    ));
+}
+
+pub fn spawn_button<'a>(parent: &'a mut ChildBuilder, label: &str, width: f32) -> EntityCommands<'a> {
+    let mut button = parent.spawn((
+        Button,
+        Node {
+            width: Val::Px(width),
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(5.0)),
+            border: UiRect::all(Val::Px(2.0)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        BackgroundColor(hex!(BUTTON_COLOR)),
+        BorderColor(Color::BLACK),
+    ));
+    button.with_child((
+        Text::new(label),
+        TextFont { font_size: 14.0, ..default() },
+        PickingBehavior::IGNORE,
+    ));
+    button
+}
+
+/// Observer for buttons that only switch state
+pub fn go_to(state: AppState) -> impl Fn(Trigger<Pointer<Down>>, ResMut<NextState<AppState>>) {
+    move |_, mut next_state| next_state.set(state)
+}
+
+fn quit(_: Trigger<Pointer<Down>>, mut exit: EventWriter<AppExit>) -> () {
+    exit.send(AppExit::Success);
+}
+
+/* Systems */
+fn spawn_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) -> () {
+    spawn_screen(&mut commands, AppState::MainMenu, hex!(BACKGROUND_COLOR)).with_children(|parent| {
+        spawn_title(parent, &asset_server, TITLE);
+        spawn_button(parent, "New game", BUTTON_WIDTH).observe(go_to(AppState::NewGame));
+        spawn_button(parent, "Quit", BUTTON_WIDTH).observe(quit);
+    });
+}
+
+/// The planet is generated on `OnEnter(AppState::Loading)`
+/// as well, so this is shown until the game starts
+fn spawn_loading(mut commands: Commands) -> () {
+    spawn_screen(&mut commands, AppState::Loading, hex!(BACKGROUND_COLOR)).with_child((
+        Text::new("Generating planet..."),
+        TextFont { font_size: 14.0, ..default() },
+    ));
+}
+
+fn spawn_pause_menu(mut commands: Commands, asset_server: Res<AssetServer>) -> () {
+    spawn_screen(&mut commands, AppState::Paused, hex!(PAUSE_BACKGROUND_COLOR)).with_children(|parent| {
+        spawn_title(parent, &asset_server, "Paused");
+        spawn_button(parent, "Resume", BUTTON_WIDTH).observe(go_to(AppState::InGame));
+        spawn_button(parent, "Quit", BUTTON_WIDTH).observe(quit);
+    });
+}
+
+/// Esc, unless it's used to cancel something. Runs before
+/// `Update` so that it sees what there is to cancel before
+/// it's cancelled.
+fn toggle_pause(
+    kb: Res<ButtonInput<KeyCode>>,
+    state: Res<State<AppState>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    preview_q: Query<(), With<TilePreview>>,
+    slot_res: Res<SlotCablePlacementResource>,
+    cable_selection: Res<CableSelection>,
+    inspector: Res<InspectorState>,
+) -> () {
+    if !kb.just_pressed(KeyCode::Escape) { return }
+    match state.get() {
+        AppState::Paused => next_state.set(AppState::InGame),
+        _ => {
+            let cancels = !preview_q.is_empty()
+                || slot_res.active().is_some()
+                || cable_selection.selected.is_some()
+                || cable_selection.dragging.is_some()
+                || inspector.is_open();
+            if !cancels { next_state.set(AppState::Paused) }
+        },
+    }
+}
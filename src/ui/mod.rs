@@ -3,3 +3,8 @@ pub mod inventory;
 pub mod stats;
 pub mod info_text;
 pub mod rocket;
+pub mod power_overlay;
+pub mod graph;
+pub mod objectives;
+pub mod menu;
+pub mod world_setup;
@@ -0,0 +1,105 @@
+/* Imports */
+use bevy::prelude::*;
+use crate::{
+    camera::UI_LAYERS,
+    components::planet::{Planet, PlayerPlanet},
+    systems::game::Objectives,
+    utils::color::hex,
+};
+
+/* Constants */
+const TRACKER_WIDTH: f32 = 180.0;
+const DONE_COLOR: &str = "#3ad13a";
+const HINT_COLOR: &str = "#c8c8c8";
+
+/// Shows the current objective and how far it is,
+/// in the top left corner below the resources
+#[derive(Component)]
+struct ObjectiveTracker;
+
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum TrackerText {
+    Goal,
+    Hint,
+    Reward,
+}
+
+pub struct ObjectiveTrackerPlugin;
+impl Plugin for ObjectiveTrackerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .add_systems(Startup, setup)
+            .add_systems(Update, update);
+    }
+}
+
+/* Systems */
+fn setup(mut commands: Commands) -> () {
+    commands.spawn((
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Px(TRACKER_WIDTH),
+            top: Val::Vh(7.0),
+            left: Val::Px(10.0),
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(3.0),
+            padding: UiRect::all(Val::Px(6.0)),
+            ..default()
+        },
+        BackgroundColor(hex!("#151515cc")),
+        ObjectiveTracker,
+        UI_LAYERS,
+        PickingBehavior::IGNORE,
+    ))
+    .with_children(|parent| {
+        for (section, font_size, color) in [
+            (TrackerText::Goal, 12.0, Color::WHITE),
+            (TrackerText::Hint, 10.0, hex!(HINT_COLOR)),
+            (TrackerText::Reward, 10.0, hex!(DONE_COLOR)),
+        ] {
+            parent.spawn((
+                Text::new(""),
+                TextFont { font_size, ..default() },
+                TextColor(color),
+                section,
+            ));
+        }
+    });
+}
+
+/// Progress is read from the planet every frame, so
+/// the tracker is live even between ticks
+fn update(
+    objectives: Res<Objectives>,
+    planet_q: Query<&Planet, With<PlayerPlanet>>,
+    mut tracker_q: Query<&mut Visibility, With<ObjectiveTracker>>,
+    mut text_q: Query<(&TrackerText, &mut Text, &mut Node)>,
+) -> () {
+    let Ok(planet) = planet_q.get_single() else { return };
+    let objective = objectives.current_objective();
+
+    for mut visibility in tracker_q.iter_mut() {
+        visibility.set_if_neq(if objective.is_some() { Visibility::Inherited } else { Visibility::Hidden });
+    }
+    let Some(objective) = objective else { return };
+
+    let (done, needed) = objective.goal.progress(planet, &objectives.produced_at_start);
+    for (section, mut text, mut node) in text_q.iter_mut() {
+        let content = match section {
+            TrackerText::Goal if needed > 1.0 => format!("{} ({:.0}/{:.0})",
+                objective.goal.description(), done.min(needed).floor(), needed),
+            TrackerText::Goal => objective.goal.description(),
+            TrackerText::Hint => objective.hint.unwrap_or_default().to_string(),
+            TrackerText::Reward => {
+                let reward: Vec<String> = objective.reward.iter()
+                    .map(|(resource, amount)| format!("{amount} {resource:?}"))
+                    .collect();
+                format!("Reward: {}", reward.join(", "))
+            },
+        };
+
+        let display = if content.is_empty() { Display::None } else { Display::Flex };
+        if node.display != display { node.display = display; }
+        if text.0 != content { text.0 = content; }
+    }
+}
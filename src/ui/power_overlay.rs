@@ -0,0 +1,205 @@
+/* Imports */
+use bevy::{color::Mix, prelude::*, sprite::Anchor, text::FontSmoothing, utils::HashMap};
+use crate::{
+    camera::{post_processing::PostProcessSettings, OuterCamera},
+    components::{cable::{cable::{Cable, CableMaterial}, slot_state::order}, planet::{Planet, PlayerPlanet}, tile::{Tile, TILE_SIZE}},
+    systems::traits::GenericTile,
+    utils::color::hex,
+};
+
+/* Constants */
+/// How fast the world is dimmed when toggling the overlay
+const DIM_SPEED: f32 = 6.0;
+const LABEL_ELEVATION: f32 = 68.0;
+const BAR_HEIGHT: f32 = 3.0;
+/// Colors need to be saturated to not be dimmed by the post processing
+const GOOD_COLOR: &str = "#3ad13a";
+const WARNING_COLOR: &str = "#e8c21a";
+const BAD_COLOR: &str = "#db1a1a";
+const GENERATOR_COLOR: &str = "#1ab0ff";
+const IDLE_CABLE_COLOR: &str = "#1f2d8a";
+
+/// Dims the world and shows how energy flows through
+/// the power grid. Toggled with G.
+#[derive(Resource, Default)]
+pub struct PowerOverlay {
+    pub active: bool,
+}
+
+/// Text and bar shown on every tile while the
+/// overlay is active
+#[derive(Component)]
+struct PowerOverlayMarker {
+    tile_id: usize,
+}
+
+impl PowerOverlay {
+    fn toggle(kb: Res<ButtonInput<KeyCode>>, mut overlay: ResMut<PowerOverlay>) -> () {
+        if kb.just_pressed(KeyCode::KeyG) {
+            overlay.active = !overlay.active;
+        }
+    }
+
+    /// Fades the world in / out
+    fn dim(
+        time: Res<Time>,
+        overlay: Res<PowerOverlay>,
+        mut settings_q: Query<&mut PostProcessSettings, With<OuterCamera>>,
+    ) -> () {
+        let target = if overlay.active { 1.0 } else { 0.0 };
+        for mut settings in settings_q.iter_mut() {
+            let step = time.delta_secs() * DIM_SPEED;
+            settings.overlay_dim += (target - settings.overlay_dim).clamp(-step, step);
+        }
+    }
+
+    /// Cables are colored by how much of their
+    /// throughput was used during the last tick
+    fn update_cables(
+        overlay: Res<PowerOverlay>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        cables_q: Query<(&Cable, &MeshMaterial2d<CableMaterial>)>,
+        mut cable_materials: ResMut<Assets<CableMaterial>>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        for (cable, mesh_material) in cables_q.iter() {
+            let color = if overlay.active {
+                let flow = planet.cable_flow
+                    .get(&order(cable.start_tile_id, cable.end_tile_id))
+                    .cloned().unwrap_or(0.0);
+
+                if flow > 0.0 { ratio_color(1.0 - flow / cable.tier.max_throughput()) }
+                else { hex!(IDLE_CABLE_COLOR).to_linear() }
+            } else {
+                LinearRgba::NONE
+            };
+
+            // Only touch the material if it changed, it
+            // would otherwise be re-uploaded every frame
+            if cable_materials.get(&mesh_material.0).is_some_and(|m| m.overlay_color != color) {
+                if let Some(material) = cable_materials.get_mut(&mesh_material.0) {
+                    material.overlay_color = color;
+                }
+            }
+        }
+    }
+
+    /// Makes sure every tile has a marker while the overlay is
+    /// active. Tiles are respawned when upgraded, so we also
+    /// check that the marker has the right parent.
+    fn update_markers(
+        mut commands: Commands,
+        overlay: Res<PowerOverlay>,
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        marker_q: Query<(Entity, &PowerOverlayMarker, &Parent)>,
+        asset_server: Res<AssetServer>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        let mut existing: HashMap<usize, Entity> = HashMap::new();
+
+        for (entity, marker, parent) in marker_q.iter() {
+            let still_needed = overlay.active && planet.tiles.get(&marker.tile_id)
+                .is_some_and(|tile| tile.entity == parent.get());
+
+            if still_needed {
+                existing.insert(marker.tile_id, entity);
+            } else {
+                commands.entity(entity).despawn_recursive();
+            }
+        }
+
+        if !overlay.active { return }
+        for tile in planet.tiles.values() {
+            if existing.contains_key(&tile.tile_id) { continue };
+            let Some(mut tile_entity) = commands.get_entity(tile.entity) else { continue };
+
+            tile_entity.with_children(|parent| {
+                parent.spawn((
+                    Text2d::new(""),
+                    TextFont {
+                        font: asset_server.load("fonts/ByteBounce.ttf"),
+                        font_size: 12.0,
+                        font_smoothing: FontSmoothing::None,
+                    },
+                    Transform::from_xyz(0.0, LABEL_ELEVATION, 5.0),
+                    PowerOverlayMarker { tile_id: tile.tile_id },
+                ))
+                .with_child((
+                    Sprite {
+                        custom_size: Some(Vec2::new(tile.width() as f32 * TILE_SIZE, BAR_HEIGHT)),
+                        anchor: Anchor::BottomCenter,
+                        ..default()
+                    },
+                    Transform::from_xyz(0.0, -LABEL_ELEVATION, 0.0),
+                ));
+            });
+        }
+    }
+
+    /// Generators show their output, consumers how much
+    /// of what they wanted they got, and batteries how
+    /// full they are.
+    fn update_labels(
+        planet_q: Query<&Planet, With<PlayerPlanet>>,
+        mut marker_q: Query<(&PowerOverlayMarker, &mut Text2d, &mut TextColor, &Children)>,
+        mut bar_q: Query<&mut Sprite>,
+    ) -> () {
+        let Ok(planet) = planet_q.get_single() else { return };
+        for (marker, mut text, mut text_color, children) in marker_q.iter_mut() {
+            let Some(tile) = planet.tiles.get(&marker.tile_id) else { continue };
+            let (label, color) = Self::label(tile);
+
+            text.0 = label;
+            text_color.0 = color;
+            for child in children.iter() {
+                if let Ok(mut sprite) = bar_q.get_mut(*child) { sprite.color = color; }
+            }
+        }
+    }
+
+    fn label(tile: &Tile) -> (String, Color) {
+        if let Some(output) = tile.energy_output() {
+            return (format!("+{output:.1}"), hex!(GENERATOR_COLOR));
+        }
+        if let Some(satisfaction) = tile.satisfaction() {
+            return (format!("{:.0}%", satisfaction * 100.0), ratio_color(satisfaction).into());
+        }
+        if tile.can_recieve_energy() {
+            let level = tile.powergrid_status.energy_stored / tile.energy_capacity();
+            return (format!("{:.0}/{:.0}",
+                tile.powergrid_status.energy_stored,
+                tile.energy_capacity()
+            ), ratio_color(level).into());
+        }
+
+        (String::new(), Color::NONE)
+    }
+}
+
+/// Red at 0.0, yellow at 0.5 and green at 1.0
+fn ratio_color(ratio: f32) -> LinearRgba {
+    let ratio = ratio.clamp(0.0, 1.0);
+    let (bad, warning, good) = (
+        hex!(BAD_COLOR).to_linear(),
+        hex!(WARNING_COLOR).to_linear(),
+        hex!(GOOD_COLOR).to_linear(),
+    );
+
+    if ratio < 0.5 { bad.mix(&warning, ratio * 2.0) }
+    else { warning.mix(&good, (ratio - 0.5) * 2.0) }
+}
+
+pub struct PowerOverlayPlugin;
+impl Plugin for PowerOverlayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<PowerOverlay>()
+            .add_systems(Update, (
+                PowerOverlay::toggle,
+                PowerOverlay::dim,
+                PowerOverlay::update_cables,
+                PowerOverlay::update_markers,
+                PowerOverlay::update_labels,
+            ).chain());
+    }
+}
@@ -1,6 +1,6 @@
-use bevy::prelude::*;
+use bevy::{picking::{focus::HoverMap, pointer::PointerId}, prelude::*};
 
-use crate::{camera::UI_LAYERS, components::{planet::{Planet, PlayerPlanet}, tile::{upgrade::UpgradeTileCommand, RemoveTileCommand, Tile}}, systems::traits::GenericTile, utils::color::hex};
+use crate::{camera::UI_LAYERS, components::{cable::slot::CableSlot, planet::{Planet, PlayerPlanet}, tile::{damage::RepairTileCommand, upgrade::UpgradeTileCommand, RemoveTileCommand, Tile, TileType}}, systems::{game::{PlanetResource, Research}, traits::{GenericTile, TileStat}}, utils::color::hex};
 
 #[derive(Event, Resource, Clone)]
 pub struct OpenStats {
@@ -8,44 +8,63 @@ pub struct OpenStats {
     pub tile_id: Option<usize>,
 }
 
+/// Which tile the inspector is showing, if any
 #[derive(Default, Resource)]
-struct StatsUIState {
-    stats: Option<OpenStats>,
+pub(crate) struct InspectorState {
+    tile_id: Option<usize>,
+}
+
+impl InspectorState {
+    pub(crate) fn is_open(&self) -> bool {
+        self.tile_id.is_some()
+    }
 }
 
 #[derive(Component)]
 struct StatsUI;
 
-#[derive(Component)]
-struct Label;
+/// The text sections of the inspector
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum InspectorText {
+    Summary,
+    Power,
+    Connections,
+    Range,
+    Research,
+    Upgrades,
+}
 
-#[derive(Component)]
-struct TileUpgradeButton;
-#[derive(Component)]
-struct TileRemovalButton;
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum InspectorButton {
+    Delete,
+    Upgrade,
+    Repair,
+    Priority,
+    Toggle,
+}
 
 pub struct StatsPlugin;
 impl Plugin for StatsPlugin {
     fn build(&self, app: &mut App) {
         app
             .add_systems(Startup, setup)
-            // .init_resource::<Events<OpenStats>>()
             .add_event::<OpenStats>()
-            .init_resource::<StatsUIState>()
-            .add_systems(Update, update);
+            .init_resource::<InspectorState>()
+            .add_systems(Update, (read_events, close, update).chain());
     }
 }
 
-fn setup(mut commands: Commands, _asset_server: Res<AssetServer>) {
+fn setup(mut commands: Commands) {
     commands.spawn((
         Node {
             position_type: PositionType::Absolute,
             width: Val::Percent(50.0),
-            height: Val::Vh(15.0),
+            min_height: Val::Vh(15.0),
             bottom: Val::Px(10.0),
             left: Val::Vw(25.0),
             flex_direction: FlexDirection::Row,
             justify_content: JustifyContent::SpaceBetween,
+            column_gap: Val::Px(16.0),
             padding: UiRect::all(Val::Px(10.0)),
             ..default()
         },
@@ -55,167 +74,356 @@ fn setup(mut commands: Commands, _asset_server: Res<AssetServer>) {
         Visibility::Hidden,
     ))
     .with_children(|parent| {
-        parent.spawn((
-            Text::new("Type: [], Energy: 0"),
-            TextFont {
-                font_size: 14.0,
-                ..default()
-            },
-            Label,
-        ));
-
-        /* Delete button */
-        parent.spawn((
-            Transform::from_xyz(0.0, 10.0, 10.0),
-            Button,
-            Node {
-                width: Val::Px(150.0),
-                height: Val::Px(65.0),
-                border: UiRect::all(Val::Px(5.0)),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..default()
-            },
-            BorderColor(Color::BLACK),
-            BorderRadius::MAX,
-            TileRemovalButton
-        )).with_child((
-            Text::new("Delete"),
-            TextFont {
-                font_size: 12.0,
-                ..default()
-            },
-        ))
-        .observe(on_delete);
-
-        /* Upgrade tile button */
-        parent.spawn((
-            Transform::from_xyz(0.0, 10.0, 10.0),
-            Button,
-            Node {
-                width: Val::Px(250.0),
-                height: Val::Px(65.0),
-                border: UiRect::all(Val::Px(5.0)),
-                justify_content: JustifyContent::Center,
-                align_items: AlignItems::Center,
-                ..default()
-            },
-            BorderColor(Color::BLACK),
-            BorderRadius::MAX,
-            TileUpgradeButton
-        )).with_child((
-            Text::new("Upgrade"),
-            TextFont {
-                font_size: 12.0,
-                ..default()
-            },
-        ))
-        .observe(on_upgrade);
+        /* Tile info */
+        parent.spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(6.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_text(parent, InspectorText::Summary, 14.0);
+            spawn_text(parent, InspectorText::Power, 11.0);
+            spawn_text(parent, InspectorText::Connections, 11.0);
+            spawn_text(parent, InspectorText::Range, 11.0);
+            spawn_text(parent, InspectorText::Research, 11.0);
+        });
+
+        /* Upgrade path */
+        parent.spawn(Node {
+            flex_direction: FlexDirection::Column,
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_text(parent, InspectorText::Upgrades, 11.0);
+        });
+
+        /* Buttons */
+        parent.spawn(Node {
+            flex_direction: FlexDirection::Column,
+            row_gap: Val::Px(4.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            spawn_button(parent, InspectorButton::Delete, "Delete").observe(on_delete);
+            spawn_button(parent, InspectorButton::Upgrade, "Upgrade").observe(on_upgrade);
+            spawn_button(parent, InspectorButton::Repair, "Repair").observe(on_repair);
+            spawn_button(parent, InspectorButton::Priority, "Priority").observe(on_priority);
+            spawn_button(parent, InspectorButton::Toggle, "Toggle").observe(on_toggle);
+        });
     });
 }
 
-// Ugly function please rewrite
-fn update(
-    mut events: EventReader<OpenStats>,
-    mut query: Query<&mut Visibility, With<StatsUI>>,
-    mut remove_btn_visibility_q: Query<&mut Visibility, (With<TileRemovalButton>, Without<Label>, Without<StatsUI>, Without<TileUpgradeButton>)>,
-    mut label: Query<&mut Text, With<Label>>,
-    mut ui_state: ResMut<StatsUIState>,
-    mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
-    mut tile_upgrade_button: Query<(&mut Visibility, &Children), (With<TileUpgradeButton>, Without<Label>, Without<StatsUI>, Without<TileRemovalButton>)>,
-    mut tile_upgrade_button_text: Query<&mut Text, (Without<Label>, Without<StatsUI>)>,
-) {
-    let planet = planet_q.single_mut();
-    let mut ui_visibility = query.single_mut();
+fn spawn_text(parent: &mut ChildBuilder, section: InspectorText, font_size: f32) -> () {
+    parent.spawn((
+        Text::new(""),
+        TextFont {
+            font_size,
+            ..default()
+        },
+        section,
+    ));
+}
 
-    // Handle open/close events first
+fn spawn_button<'a>(parent: &'a mut ChildBuilder, button: InspectorButton, label: &str) -> EntityCommands<'a> {
+    let mut entity = parent.spawn((
+        Button,
+        Node {
+            width: Val::Px(170.0),
+            padding: UiRect::axes(Val::Px(8.0), Val::Px(5.0)),
+            border: UiRect::all(Val::Px(3.0)),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        BorderColor(Color::BLACK),
+        BorderRadius::MAX,
+        button,
+    ));
+    entity.with_child((
+        Text::new(label),
+        TextFont {
+            font_size: 12.0,
+            ..default()
+        },
+    ));
+    entity
+}
+
+fn read_events(mut events: EventReader<OpenStats>, mut state: ResMut<InspectorState>) -> () {
     for event in events.read() {
-        *ui_visibility = if event.open {
-            Visibility::Visible
-        } else {
-            Visibility::Hidden
-        };
+        state.tile_id = if event.open { event.tile_id } else { None };
+    }
+}
+
+/// Closes the inspector on Escape, or when clicking something
+/// that isn't a tile, cable slot or the inspector itself
+fn close(
+    kb: Res<ButtonInput<KeyCode>>,
+    mouse: Res<ButtonInput<MouseButton>>,
+    hover_map: Res<HoverMap>,
+    parent_q: Query<&Parent>,
+    keep_open_q: Query<(), Or<(With<StatsUI>, With<CableSlot>)>>,
+    planet_q: Query<&Planet, With<PlayerPlanet>>,
+    mut state: ResMut<InspectorState>,
+) -> () {
+    if state.tile_id.is_none() { return }
+    if kb.just_pressed(KeyCode::Escape) {
+        state.tile_id = None;
+        return;
+    }
+    if !mouse.just_pressed(MouseButton::Left) { return }
+
+    let Ok(planet) = planet_q.get_single() else { return };
+    let keeps_open = |entity: Entity| keep_open_q.contains(entity)
+        || planet.tiles.values().any(|tile| tile.entity == entity);
+
+    let clicked_inside = hover_map.get(&PointerId::Mouse).is_some_and(|hovered| {
+        hovered.keys().any(|entity| {
+            keeps_open(*entity) || parent_q.iter_ancestors(*entity).any(keeps_open)
+        })
+    });
+    if !clicked_inside { state.tile_id = None; }
+}
+
+/// Refreshes everything every frame while open
+/// so that the numbers stay live
+fn update(
+    mut state: ResMut<InspectorState>,
+    planet_q: Query<&Planet, With<PlayerPlanet>>,
+    mut ui_q: Query<&mut Visibility, With<StatsUI>>,
+    mut text_q: Query<(&InspectorText, &mut Text)>,
+    mut button_q: Query<(&InspectorButton, &mut Node, &Children)>,
+    mut label_q: Query<&mut Text, Without<InspectorText>>,
+) -> () {
+    let Ok(planet) = planet_q.get_single() else { return };
+
+    // The tile might have been removed while open
+    let tile = state.tile_id.and_then(|tile_id| planet.tiles.get(&tile_id));
+    if tile.is_none() && state.tile_id.is_some() { state.tile_id = None; }
+
+    for mut visibility in ui_q.iter_mut() {
+        *visibility = if tile.is_some() { Visibility::Visible } else { Visibility::Hidden };
+    }
+    let Some(tile) = tile else { return };
 
-        ui_state.stats = if event.open {
-            Some(event.clone())
-        } else {
-            None
+    for (section, mut text) in text_q.iter_mut() {
+        text.0 = match section {
+            InspectorText::Summary => summary(tile),
+            InspectorText::Power => power(tile),
+            InspectorText::Connections => connections(tile, planet),
+            InspectorText::Range => range(tile, planet),
+            InspectorText::Research => research(tile, planet),
+            InspectorText::Upgrades => upgrade_path(tile),
         };
     }
 
-    // If UI is closed, ensure everything is hidden and exit early
-    if *ui_visibility == Visibility::Hidden {
-        for (mut button_vis, _) in &mut tile_upgrade_button {
-            *button_vis = Visibility::Hidden;
+    for (button, mut node, children) in button_q.iter_mut() {
+        let label = match button {
+            InspectorButton::Delete => (!tile.tile_type.indestructible())
+                .then(|| "Delete".to_string()),
+            InspectorButton::Upgrade => tile.next_upgrade()
+                .map(|upgrade| match Some(tile.tile_level + 1)
+                    .filter(|level| !planet.research.is_upgrade_unlocked(*level))
+                    .and_then(Research::required_for_upgrade) {
+                    Some(technology) => format!("Needs {}", technology.display_name()),
+                    None => format!("Upgrade ({})", format_cost(&upgrade.cost)),
+                }),
+            InspectorButton::Repair => Some(tile.repair_cost())
+                .filter(|cost| !cost.is_empty())
+                .map(|cost| format!("Repair ({})", format_cost(&cost))),
+            InspectorButton::Priority => tile.can_recieve_energy()
+                .then(|| format!("Priority: {}", tile.powergrid_status.priority.display_name())),
+            InspectorButton::Toggle => tile.tile_type.toggle_label(tile),
+        };
+
+        node.display = if label.is_some() { Display::Flex } else { Display::None };
+        if let (Some(label), Some(mut text)) = (label, children.first().and_then(|c| label_q.get_mut(*c).ok())) {
+            text.0 = label;
         }
-        return;
     }
+}
 
-    // Only update contents if UI is visible
-    let Some(stats) = &ui_state.stats else { return };
-    let Some(tile_id) = &stats.tile_id else { return };
-    let tile = planet.tiles[tile_id].clone();
-    
-    // Update main label
-    for mut text in &mut label {
-        text.0 = format!("{}\nEnergy: {}\nLevel: {}",
-            tile.tile_type.display_name(),
-            tile.powergrid_status.energy_stored,
-            tile.tile_level,
-        );
-    }
-
-    // Update delete button visibility based on tile type
-    if let Ok(mut vis) = remove_btn_visibility_q.get_single_mut() {
-        if tile.tile_type.indestructible() {
-            *vis = Visibility::Hidden;
-        }
+fn summary(tile: &Tile) -> String {
+    let condition = if tile.is_broken() { " - Broken!".to_string() }
+        else if tile.efficiency() < 1.0 { format!(" - {:.0}% efficiency", tile.efficiency() * 100.0) }
+        else { String::new() };
+
+    format!("{} (level {})\nHealth: {:.0} / {:.0}{}{}",
+        tile.tile_type.display_name(),
+        tile.tile_level,
+        tile.health.max(0.0),
+        tile.max_health(),
+        condition,
+        if tile.powergrid_status.powered || tile.is_broken() { "" } else { "\nNo power!" },
+    )
+}
+
+/// Energy in / out per tick and what's stored
+fn power(tile: &Tile) -> String {
+    let status = &tile.powergrid_status;
+    let mut lines = Vec::new();
+
+    if let Some(output) = tile.energy_output() {
+        lines.push(format!("Output: {output:.1} / tick"));
+    }
+    if let Some(consumption) = tile.tile_type.energy_consumption(tile) {
+        lines.push(format!("Consumes: {consumption:.1} / tick"));
+    }
+    if tile.can_recieve_energy() {
+        lines.push(format!("Received: {:.1} of {:.1} / tick", status.energy_received, status.energy_demanded));
+        lines.push(format!("Stored: {:.1} / {:.1}", status.energy_stored, tile.energy_capacity()));
+    }
+    if !status.conducting {
+        lines.push("Not conducting".to_string());
     }
 
-    // Update upgrade button
-    for (mut button_vis, children) in &mut tile_upgrade_button {
-        let max_level = tile.tile_type.upgrades().len().saturating_sub(1);
-        
-        if tile.tile_level >= max_level {
-            *button_vis = Visibility::Hidden;
-        } else {
-            *button_vis = Visibility::Visible;
-            
-            // Update cost text
-            if let Ok(mut text) = tile_upgrade_button_text.get_mut(children[0]) {
-                let costs: Vec<String> = tile.tile_type.upgrades()[tile.tile_level]
-                    .iter()
-                    .map(|(resource, amount)| format!("{} {:?}", amount, resource))
-                    .collect();
-                text.0 = format!("Upgrade ({})", costs.join(", "));
-            }
-        }
+    lines.join("\n")
+}
+
+fn connections(tile: &Tile, planet: &Planet) -> String {
+    let connected = &tile.powergrid_status.connected_tiles;
+    if connected.is_empty() { return "Not connected".to_string() }
+
+    let lines: Vec<String> = connected.iter()
+        .filter_map(|id| planet.tiles.get(id))
+        .map(|other| format!("  {} #{} ({})",
+            other.tile_type.display_name(),
+            other.tile_id,
+            planet.cable_tier(tile.tile_id, other.tile_id).display_name(),
+        ))
+        .collect();
+
+    format!("Connected to:\n{}", lines.join("\n"))
+}
+
+/// Interaction range, and for tiles that interact with
+/// POI:s (like drills) how many are in range
+fn range(tile: &Tile, planet: &Planet) -> String {
+    if tile.range() == 0 { return String::new() }
+    let mut text = format!("Range: {} tiles", tile.range());
+
+    for poi_type in tile.tile_type.interacts_with() {
+        let count = planet.numbers_in_radius(tile.tile_id, tile.range()).iter()
+            .filter_map(|index| planet.points_of_interest.get(index))
+            .flatten()
+            .filter(|poi| poi.poi_type == poi_type)
+            .count();
+        text.push_str(&format!("\n  {} in range: {count}", poi_type.display_name()));
+    }
+    for (resource, amount) in tile.tile_type.expected_yield(tile, planet) {
+        text.push_str(&format!("\n  Mines {amount:.2} {resource:?} / tick"));
+    }
+
+    text
+}
+
+/// What research labs are working on
+fn research(tile: &Tile, planet: &Planet) -> String {
+    if !matches!(tile.tile_type, TileType::ResearchLab(_)) { return String::new() }
+
+    let Some((technology, progress)) = planet.research.current() else {
+        return match planet.research.available().first() {
+            Some(technology) => format!("Will research: {}", technology.display_name()),
+            None => "Everything is researched".to_string(),
+        };
+    };
+    let unlocks: Vec<String> = technology.unlocks_tiles().iter()
+        .map(|tile_type| tile_type.display_name())
+        .collect();
+
+    let mut text = format!("Researching: {}\n  {:.1} / {:.0} points", technology.display_name(), progress, technology.cost());
+    if !unlocks.is_empty() { text.push_str(&format!("\n  Unlocks: {}", unlocks.join(", "))); }
+    if let Some(level) = technology.unlocks_upgrade_level() {
+        text.push_str(&match level {
+            usize::MAX => "\n  Unlocks: all upgrade levels".to_string(),
+            level => format!("\n  Unlocks: upgrade level {level}"),
+        });
     }
+    text
+}
+
+/// Every upgrade level with its cost and what it changes,
+/// as before -> after. Levels already reached are marked as done
+fn upgrade_path(tile: &Tile) -> String {
+    let upgrades = tile.tile_type.upgrades();
+    if upgrades.is_empty() { return "No upgrades".to_string() }
+
+    let lines: Vec<String> = upgrades.iter().enumerate().map(|(i, upgrade)| {
+        let level = i + 1;
+        let marker = if level <= tile.tile_level { "done" }
+            else if level == tile.tile_level + 1 { "next" }
+            else { "    " };
+
+        let mut stats: Vec<TileStat> = Vec::new();
+        for modifier in &upgrade.modifiers {
+            if !stats.contains(&modifier.stat()) { stats.push(modifier.stat()); }
+        }
+        let mut line = format!("[{marker}] Level {level} - {}", format_cost(&upgrade.cost));
+        for stat in stats {
+            line.push_str(&format!("\n  {}: {:.1} -> {:.1}",
+                stat.display_name(),
+                tile.stat_at_level(stat, level - 1),
+                tile.stat_at_level(stat, level),
+            ));
+        }
+        line
+    }).collect();
+
+    format!("Upgrades:\n{}", lines.join("\n"))
+}
+
+fn format_cost(cost: &[(PlanetResource, usize)]) -> String {
+    cost.iter()
+        .map(|(resource, amount)| format!("{amount} {resource:?}"))
+        .collect::<Vec<String>>()
+        .join(", ")
 }
 
 fn on_delete(
     _: Trigger<Pointer<Down>>,
     mut commands: Commands,
-    mut events: EventWriter<OpenStats>,
-    ui_state: Res<StatsUIState>,
+    mut state: ResMut<InspectorState>,
 ) -> () {
-    if let Some(stats) = &ui_state.stats {
-        if let Some(tile_id) = stats.tile_id {
-            commands.queue(RemoveTileCommand { tile_id });
-        }
+    if let Some(tile_id) = state.tile_id.take() {
+        commands.queue(RemoveTileCommand { tile_id });
     }
-    events.send(OpenStats { open: false, tile_id: None });
 }
 fn on_upgrade(
     _: Trigger<Pointer<Down>>,
     mut commands: Commands,
-    mut events: EventWriter<OpenStats>,
-    ui_state: Res<StatsUIState>,
+    state: Res<InspectorState>,
 ) -> () {
-    if let Some(stats) = &ui_state.stats {
-        if let Some(tile_id) = stats.tile_id {
-            commands.queue(UpgradeTileCommand { tile_id });
-        }
+    if let Some(tile_id) = state.tile_id {
+        commands.queue(UpgradeTileCommand { tile_id });
+    }
+}
+fn on_repair(
+    _: Trigger<Pointer<Down>>,
+    mut commands: Commands,
+    state: Res<InspectorState>,
+) -> () {
+    if let Some(tile_id) = state.tile_id {
+        commands.queue(RepairTileCommand { tile_id });
+    }
+}
+fn on_priority(
+    _: Trigger<Pointer<Down>>,
+    mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+    state: Res<InspectorState>,
+) -> () {
+    let Ok(mut planet) = planet_q.get_single_mut() else { return };
+    let Some(tile_id) = state.tile_id else { return };
+    if let Some(tile) = planet.tiles.get_mut(&tile_id) {
+        tile.powergrid_status.priority = tile.powergrid_status.priority.next();
     }
-    events.send(OpenStats { open: false, tile_id: None });
+}
+fn on_toggle(
+    _: Trigger<Pointer<Down>>,
+    mut planet_q: Query<&mut Planet, With<PlayerPlanet>>,
+    state: Res<InspectorState>,
+) -> () {
+    let Ok(mut planet) = planet_q.get_single_mut() else { return };
+    let Some(tile_id) = state.tile_id else { return };
+    let Some(tile_type) = planet.tiles.get(&tile_id).map(|tile| tile.tile_type.clone()) else { return };
+    tile_type.on_toggle(tile_id, &mut planet);
 }
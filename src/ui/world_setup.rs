@@ -0,0 +1,390 @@
+/* Imports */
+use bevy::{
+    input::{keyboard::{Key, KeyboardInput}, ButtonState},
+    prelude::*,
+    render::{camera::RenderTarget, render_resource::{Extent3d, TextureDescriptor, TextureDimension, TextureFormat, TextureUsages}},
+};
+use crate::{
+    camera::PREVIEW_LAYERS,
+    components::planet::{debug::PlanetConfiguration, mesh::generate_planet_mesh, Planet},
+    systems::game::AppState,
+    utils::color::hex,
+};
+use super::menu::{go_to, spawn_button, spawn_screen, spawn_title, BACKGROUND_COLOR, BUTTON_WIDTH};
+
+/* Constants */
+/// Width and height of the planet preview (px)
+const PREVIEW_SIZE: f32 = 220.0;
+/// How much room there is around the planet in the preview
+const PREVIEW_MARGIN: f32 = 1.15;
+const PREVIEW_PLANET_COLOR: &str = "#6b4a2f";
+const ARROW_WIDTH: f32 = 26.0;
+const EDITING_COLOR: &str = "#e8c21a";
+/// Seeds are typed in, and kept below `u32::MAX`
+const MAX_SEED_DIGITS: usize = 9;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum WorldSize {
+    Small,
+    Medium,
+    Large,
+}
+
+impl WorldSize {
+    const ALL: [WorldSize; 3] = [Self::Small, Self::Medium, Self::Large];
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::Small => "Small",
+            Self::Medium => "Medium",
+            Self::Large => "Large",
+        }
+    }
+
+    /// (radius, resolution). Bigger planets need more
+    /// points to keep the surface as detailed.
+    fn shape(&self) -> (f32, usize) {
+        match self {
+            Self::Small => (900.0, 320),
+            Self::Medium => (1400.0, 500),
+            Self::Large => (2200.0, 780),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Roughness {
+    Flat,
+    Hilly,
+    Rough,
+    Extreme,
+}
+
+impl Roughness {
+    const ALL: [Roughness; 4] = [Self::Flat, Self::Hilly, Self::Rough, Self::Extreme];
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::Flat => "Flat",
+            Self::Hilly => "Hilly",
+            Self::Rough => "Rough",
+            Self::Extreme => "Extreme",
+        }
+    }
+
+    /// (amplitude, frequency) of the surface noise
+    fn noise(&self) -> (f32, f64) {
+        match self {
+            Self::Flat => (600.0, 50.0),
+            Self::Hilly => (2000.0, 80.0),
+            Self::Rough => (3200.0, 120.0),
+            Self::Extreme => (4500.0, 180.0),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Abundance {
+    Scarce,
+    Normal,
+    Plentiful,
+}
+
+impl Abundance {
+    const ALL: [Abundance; 3] = [Self::Scarce, Self::Normal, Self::Plentiful];
+
+    fn display_name(&self) -> &'static str {
+        match self {
+            Self::Scarce => "Scarce",
+            Self::Normal => "Normal",
+            Self::Plentiful => "Plentiful",
+        }
+    }
+
+    /// See [`PlanetConfiguration::resource_abundance`]
+    fn multiplier(&self) -> f32 {
+        match self {
+            Self::Scarce => 0.5,
+            Self::Normal => 1.0,
+            Self::Plentiful => 1.8,
+        }
+    }
+}
+
+/// The presets picked on the world setup screen,
+/// they're written to the [`PlanetConfiguration`]
+#[derive(Resource)]
+struct WorldSetup {
+    size: WorldSize,
+    roughness: Roughness,
+    abundance: Abundance,
+
+    /// If digits typed go to the seed
+    editing_seed: bool,
+}
+
+impl Default for WorldSetup {
+    fn default() -> Self {
+        Self {
+            size: WorldSize::Medium,
+            roughness: Roughness::Hilly,
+            abundance: Abundance::Normal,
+            editing_seed: false,
+        }
+    }
+}
+
+impl WorldSetup {
+    fn apply(&self, config: &mut PlanetConfiguration) -> () {
+        (config.radius, config.resolution) = self.size.shape();
+        (config.amplitude, config.frequency) = self.roughness.noise();
+        config.resource_abundance = self.abundance.multiplier();
+    }
+}
+
+/// Something that can be changed on the world setup screen
+#[derive(Component, Clone, Copy, PartialEq, Eq)]
+enum SetupOption {
+    Seed,
+    Size,
+    Roughness,
+    Abundance,
+}
+
+impl SetupOption {
+    fn label(&self, setup: &WorldSetup, config: &PlanetConfiguration) -> String {
+        match self {
+            Self::Seed if setup.editing_seed => format!("Seed: {}_", config.seed),
+            Self::Seed => format!("Seed: {}", config.seed),
+            Self::Size => format!("Size: {}", setup.size.display_name()),
+            Self::Roughness => format!("Terrain: {}", setup.roughness.display_name()),
+            Self::Abundance => format!("Resources: {}", setup.abundance.display_name()),
+        }
+    }
+
+    /// Picks the next (or previous, if `forward` is false) preset
+    fn cycle(&self, setup: &mut WorldSetup, forward: bool) -> () {
+        fn next<T: Copy + PartialEq>(all: &[T], current: T, forward: bool) -> T {
+            let index = all.iter().position(|value| *value == current).unwrap_or(0);
+            let offset = if forward { 1 } else { all.len() - 1 };
+            all[(index + offset) % all.len()]
+        }
+
+        match self {
+            Self::Seed => (),
+            Self::Size => setup.size = next(&WorldSize::ALL, setup.size, forward),
+            Self::Roughness => setup.roughness = next(&Roughness::ALL, setup.roughness, forward),
+            Self::Abundance => setup.abundance = next(&Abundance::ALL, setup.abundance, forward),
+        }
+    }
+}
+
+/// Renders the preview planet to the image shown on the screen
+#[derive(Component)]
+struct PreviewCamera;
+
+#[derive(Component)]
+struct PreviewPlanet;
+
+pub struct WorldSetupPlugin;
+impl Plugin for WorldSetupPlugin {
+    fn build(&self, app: &mut App) {
+        app
+            .init_resource::<WorldSetup>()
+            .add_systems(OnEnter(AppState::NewGame), setup)
+            .add_systems(Update, (
+                type_seed,
+                apply_presets,
+                update_labels,
+                update_preview,
+            ).chain().run_if(in_state(AppState::NewGame)));
+    }
+}
+
+/* Systems */
+fn setup(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut images: ResMut<Assets<Image>>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<ColorMaterial>>,
+    mut setup: ResMut<WorldSetup>,
+    mut config: ResMut<PlanetConfiguration>,
+) -> () {
+    setup.editing_seed = false;
+    setup.apply(&mut config);
+
+    /* Preview */
+    let size = Extent3d {
+        width: PREVIEW_SIZE as u32,
+        height: PREVIEW_SIZE as u32,
+        ..default()
+    };
+    let mut image = Image {
+        texture_descriptor: TextureDescriptor {
+            label: None,
+            size,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Bgra8UnormSrgb,
+            mip_level_count: 1,
+            sample_count: 1,
+            usage: TextureUsages::TEXTURE_BINDING
+                | TextureUsages::COPY_DST
+                | TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        },
+        ..default()
+    };
+    image.resize(size);
+    let image = images.add(image);
+
+    commands.spawn((
+        Camera2d,
+        Camera {
+            order: -1,
+            target: RenderTarget::Image(image.clone()),
+            clear_color: ClearColorConfig::Custom(hex!(BACKGROUND_COLOR)),
+            ..default()
+        },
+        OrthographicProjection::default_2d(),
+        PreviewCamera,
+        PREVIEW_LAYERS,
+        StateScoped(AppState::NewGame),
+    ));
+    commands.spawn((
+        Mesh2d(generate_planet_mesh(&mut meshes, &Planet::get_surface_radii(&config))),
+        MeshMaterial2d(materials.add(ColorMaterial::from_color(hex!(PREVIEW_PLANET_COLOR)))),
+        PreviewPlanet,
+        PREVIEW_LAYERS,
+        StateScoped(AppState::NewGame),
+    ));
+
+    /* Options */
+    spawn_screen(&mut commands, AppState::NewGame, hex!(BACKGROUND_COLOR)).with_children(|parent| {
+        spawn_title(parent, &asset_server, "New world");
+
+        parent.spawn(Node {
+            align_items: AlignItems::Center,
+            column_gap: Val::Px(24.0),
+            ..default()
+        })
+        .with_children(|parent| {
+            parent.spawn(Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(6.0),
+                ..default()
+            })
+            .with_children(|parent| {
+                parent.spawn(Node { column_gap: Val::Px(6.0), ..default() }).with_children(|row| {
+                    spawn_button(row, "", BUTTON_WIDTH)
+                        .insert(SetupOption::Seed)
+                        .observe(|_: Trigger<Pointer<Down>>, mut setup: ResMut<WorldSetup>| {
+                            setup.editing_seed = !setup.editing_seed;
+                        });
+                    spawn_button(row, "Random", ARROW_WIDTH * 3.0).observe(
+                        |_: Trigger<Pointer<Down>>, mut setup: ResMut<WorldSetup>, mut config: ResMut<PlanetConfiguration>| {
+                            setup.editing_seed = false;
+                            config.seed = rand::random::<u32>() % 10u32.pow(MAX_SEED_DIGITS as u32);
+                        });
+                });
+
+                for option in [SetupOption::Size, SetupOption::Roughness, SetupOption::Abundance] {
+                    parent.spawn(Node { column_gap: Val::Px(6.0), ..default() }).with_children(|row| {
+                        spawn_button(row, "<", ARROW_WIDTH).observe(
+                            move |_: Trigger<Pointer<Down>>, mut setup: ResMut<WorldSetup>| option.cycle(&mut setup, false));
+                        spawn_button(row, "", BUTTON_WIDTH).insert(option);
+                        spawn_button(row, ">", ARROW_WIDTH).observe(
+                            move |_: Trigger<Pointer<Down>>, mut setup: ResMut<WorldSetup>| option.cycle(&mut setup, true));
+                    });
+                }
+            });
+
+            parent.spawn((
+                ImageNode::new(image),
+                Node {
+                    width: Val::Px(PREVIEW_SIZE),
+                    height: Val::Px(PREVIEW_SIZE),
+                    ..default()
+                },
+            ));
+        });
+
+        parent.spawn(Node {
+            column_gap: Val::Px(6.0),
+            margin: UiRect::top(Val::Px(16.0)),
+            ..default()
+        })
+        .with_children(|row| {
+            spawn_button(row, "Back", BUTTON_WIDTH).observe(go_to(AppState::MainMenu));
+            spawn_button(row, "Start", BUTTON_WIDTH).observe(go_to(AppState::Loading));
+        });
+    });
+}
+
+/// Digits are added to the seed while it's being
+/// edited, enter or escape stops editing
+fn type_seed(
+    mut events: EventReader<KeyboardInput>,
+    mut setup: ResMut<WorldSetup>,
+    mut config: ResMut<PlanetConfiguration>,
+) -> () {
+    for event in events.read() {
+        if !setup.editing_seed || event.state != ButtonState::Pressed { continue }
+        match &event.logical_key {
+            Key::Character(text) => {
+                let Some(digit) = text.chars().next().and_then(|c| c.to_digit(10)) else { continue };
+                if config.seed.to_string().len() >= MAX_SEED_DIGITS { continue }
+                config.seed = config.seed * 10 + digit;
+            },
+            Key::Backspace => config.seed /= 10,
+            Key::Enter | Key::Escape => setup.editing_seed = false,
+            _ => (),
+        }
+    }
+}
+
+fn apply_presets(setup: Res<WorldSetup>, mut config: ResMut<PlanetConfiguration>) -> () {
+    if !setup.is_changed() { return }
+    setup.apply(&mut config);
+}
+
+/// The labels are the text of the option buttons, the
+/// seed is outlined while it's being edited
+fn update_labels(
+    setup: Res<WorldSetup>,
+    config: Res<PlanetConfiguration>,
+    mut option_q: Query<(&SetupOption, &Children, &mut BorderColor)>,
+    mut text_q: Query<&mut Text>,
+) -> () {
+    if !setup.is_changed() && !config.is_changed() { return }
+    for (option, children, mut border) in option_q.iter_mut() {
+        for child in children.iter() {
+            let Ok(mut text) = text_q.get_mut(*child) else { continue };
+            text.0 = option.label(&setup, &config);
+        }
+        if *option == SetupOption::Seed {
+            border.0 = if setup.editing_seed { hex!(EDITING_COLOR) } else { Color::BLACK };
+        }
+    }
+}
+
+/// Regenerates the preview mesh and zooms out
+/// so that the whole planet fits
+fn update_preview(
+    config: Res<PlanetConfiguration>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut planet_q: Query<&mut Mesh2d, With<PreviewPlanet>>,
+    mut camera_q: Query<&mut OrthographicProjection, With<PreviewCamera>>,
+) -> () {
+    if !config.is_changed() { return }
+    let radii = Planet::get_surface_radii(&config);
+    let max_radius = radii.iter().map(|(_, radius)| *radius).fold(0.0, f32::max);
+
+    for mut mesh in planet_q.iter_mut() {
+        mesh.0 = generate_planet_mesh(&mut meshes, &radii);
+    }
+    for mut projection in camera_q.iter_mut() {
+        projection.scale = max_radius * 2.0 * PREVIEW_MARGIN / PREVIEW_SIZE;
+    }
+}
@@ -1,3 +1,5 @@
 pub mod color;
 pub mod logger;
 pub mod audio;
+pub mod ring_buffer;
+pub mod save;
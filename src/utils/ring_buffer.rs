@@ -0,0 +1,60 @@
+/* Imports */
+use std::collections::VecDeque;
+
+/// A buffer with a fixed capacity. Pushing to a full
+/// buffer drops the oldest item.
+#[derive(Clone, Debug)]
+pub struct RingBuffer<T> {
+    items: VecDeque<T>,
+    capacity: usize,
+}
+
+impl<T> RingBuffer<T> {
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "Capacity must be at least 1.");
+        Self { items: VecDeque::with_capacity(capacity), capacity }
+    }
+
+    pub fn push(&mut self, item: T) -> () {
+        if self.items.len() == self.capacity {
+            self.items.pop_front();
+        }
+        self.items.push_back(item);
+    }
+
+    /// The `n` newest items, oldest first
+    pub fn last_n(&self, n: usize) -> impl Iterator<Item = &T> {
+        self.items.iter().skip(self.items.len().saturating_sub(n))
+    }
+
+    /// All items, oldest first
+    pub fn iter(&self) -> impl Iterator<Item = &T> { self.items.iter() }
+    pub fn last(&self) -> Option<&T> { self.items.back() }
+    pub fn len(&self) -> usize { self.items.len() }
+    pub fn is_empty(&self) -> bool { self.items.is_empty() }
+    pub fn capacity(&self) -> usize { self.capacity }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_drops_oldest() {
+        let mut buffer = RingBuffer::new(3);
+        for i in 0..5 { buffer.push(i); }
+
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.iter().cloned().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(buffer.last(), Some(&4));
+    }
+
+    #[test]
+    fn last_n() {
+        let mut buffer = RingBuffer::new(10);
+        for i in 0..4 { buffer.push(i); }
+
+        assert_eq!(buffer.last_n(2).cloned().collect::<Vec<_>>(), vec![2, 3]);
+        assert_eq!(buffer.last_n(20).cloned().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+    }
+}
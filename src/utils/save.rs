@@ -0,0 +1,88 @@
+/* Imports */
+use std::{collections::BTreeMap, fmt::Display, fs, str::FromStr};
+use super::logger;
+
+/* Constants */
+/// Where the game is saved, next to the executable
+pub const SAVE_PATH: &str = "save.txt";
+/// Lifetime stats and achievements, shared by every game
+pub const PROFILE_PATH: &str = "profile.txt";
+
+/// A plain text file with one `key=value` per line. Keys are
+/// namespaced with dots (e.g `research.unlocked`) so that every
+/// system can keep its own values in the same file.
+#[derive(Clone, Debug, Default)]
+pub struct SaveFile {
+    path: String,
+    values: BTreeMap<String, String>,
+}
+
+impl SaveFile {
+    /// Reads the file at `path`, a missing or
+    /// unreadable file is just an empty save
+    pub fn load(path: &str) -> Self {
+        let text = fs::read_to_string(path).unwrap_or_else(|e| {
+            logger::log::yellow("save", format!("Couldn't read {}: {}", path, e));
+            String::new()
+        });
+        Self { path: path.to_string(), values: Self::parse(&text) }
+    }
+
+    /// Lines without a `=` and empty lines are skipped
+    fn parse(text: &str) -> BTreeMap<String, String> {
+        text.lines()
+            .filter_map(|line| line.split_once('='))
+            .map(|(key, value)| (key.trim().to_string(), value.trim().to_string()))
+            .filter(|(key, _)| !key.is_empty())
+            .collect()
+    }
+
+    fn serialize(&self) -> String {
+        self.values.iter()
+            .map(|(key, value)| format!("{key}={value}\n"))
+            .collect()
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.values.get(key).map(|value| value.as_str())
+    }
+
+    /// Parses the value, `None` if it's missing or malformed
+    pub fn get_parsed<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.get(key).and_then(|value| value.parse().ok())
+    }
+
+    /// Values can't contain line breaks, they're replaced with spaces
+    pub fn set(&mut self, key: &str, value: impl Display) -> () {
+        self.values.insert(key.to_string(), value.to_string().replace('\n', " "));
+    }
+
+    pub fn write(&self) -> Result<(), String> {
+        fs::write(&self.path, self.serialize())
+            .map_err(|e| format!("Couldn't write {}: {}", self.path, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let mut save = SaveFile::default();
+        save.set("research.progress", 12.5);
+        save.set("research.unlocked", "mining,storage");
+
+        let loaded = SaveFile { path: String::new(), values: SaveFile::parse(&save.serialize()) };
+        assert_eq!(loaded.get_parsed::<f32>("research.progress"), Some(12.5));
+        assert_eq!(loaded.get("research.unlocked"), Some("mining,storage"));
+        assert_eq!(loaded.get("missing"), None);
+    }
+
+    #[test]
+    fn skips_malformed_lines() {
+        let values = SaveFile::parse("no equals sign\n\n =empty key\n a = b \n");
+        assert_eq!(values.len(), 1);
+        assert_eq!(values.get("a").map(|v| v.as_str()), Some("b"));
+    }
+}